@@ -1,6 +1,20 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::fmt::Write as _;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// The reference locale; every other catalog must define exactly its keys.
+const BASE_LOCALE: &str = "en";
+
+/// CLDR plural categories each shipped locale must provide (integers only), matching
+/// `plural_category` in `src/i18n/catalog.rs`.
+const PLURAL_CATEGORIES: &[(&str, &[&str])] = &[
+    ("en", &["one", "other"]),
+    ("de", &["one", "other"]),
+    ("pl", &["one", "few", "many"]),
+    ("uk", &["one", "few", "many"]),
+];
 
 fn main() {
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
@@ -41,4 +55,160 @@ fn main() {
         fs::write(&generated, "pub const ICON_PNG: &[u8] = &[];\n")
             .expect("failed to write embedded_icon_png.rs");
     }
+
+    // --- Embed translation catalogs (locales/*.toml), checked for completeness
+    embed_locales(&manifest_dir.join("locales"), &out_dir);
+}
+
+/// One catalog entry: a plain string or one string per plural category.
+enum Entry {
+    Text(String),
+    Plural(BTreeMap<String, String>),
+}
+
+/// Parses every `locales/<code>.toml`, fails the build if a locale is missing keys, has
+/// stray ones, lacks a plural category or uses placeholders the base locale doesn't, and
+/// writes them out as sorted static tables for `i18n::catalog`.
+fn embed_locales(dir: &Path, out_dir: &Path) {
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut locales = BTreeMap::new();
+    for entry in fs::read_dir(dir).expect("failed to read locales/") {
+        let path = entry.expect("failed to read locales/").path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let code = path.file_stem().unwrap().to_string_lossy().into_owned();
+        locales.insert(code, parse_locale(&path));
+    }
+
+    let base = locales
+        .get(BASE_LOCALE)
+        .unwrap_or_else(|| panic!("locales/{BASE_LOCALE}.toml is missing"));
+    let mut problems = Vec::new();
+    for (code, catalog) in &locales {
+        let categories = PLURAL_CATEGORIES
+            .iter()
+            .find(|(c, _)| c == code)
+            .map(|(_, categories)| *categories)
+            .unwrap_or_else(|| panic!("no plural rules for locale `{code}` in build.rs"));
+
+        for key in base.keys().filter(|k| !catalog.contains_key(*k)) {
+            problems.push(format!("{code}: missing key `{key}`"));
+        }
+        for key in catalog.keys().filter(|k| !base.contains_key(*k)) {
+            problems.push(format!("{code}: unknown key `{key}`"));
+        }
+
+        for (key, entry) in catalog {
+            let Some(base_entry) = base.get(key) else {
+                continue;
+            };
+            match (entry, base_entry) {
+                (Entry::Text(text), Entry::Text(base_text)) => {
+                    check_placeholders(code, key, text, &placeholders(base_text), &mut problems)
+                }
+                (Entry::Plural(forms), Entry::Plural(base_forms)) => {
+                    let allowed: BTreeSet<String> =
+                        base_forms.values().flat_map(|t| placeholders(t)).collect();
+                    for category in categories.iter().filter(|c| !forms.contains_key(**c)) {
+                        problems.push(format!("{code}: `{key}` lacks plural form `{category}`"));
+                    }
+                    for text in forms.values() {
+                        check_placeholders(code, key, text, &allowed, &mut problems);
+                    }
+                }
+                _ => problems.push(format!("{code}: `{key}` is plural in one locale only")),
+            }
+        }
+    }
+    if !problems.is_empty() {
+        panic!(
+            "translation catalogs are inconsistent:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+
+    let mut code = String::new();
+    for (locale, catalog) in &locales {
+        writeln!(
+            code,
+            "pub static {}: &[(&str, Entry)] = &[",
+            locale.to_uppercase()
+        )
+        .unwrap();
+        for (key, entry) in catalog {
+            match entry {
+                Entry::Text(text) => writeln!(code, "    ({key:?}, Entry::Text({text:?})),"),
+                Entry::Plural(forms) => {
+                    let forms: Vec<String> = forms
+                        .iter()
+                        .map(|(c, t)| format!("({c:?}, {t:?})"))
+                        .collect();
+                    writeln!(
+                        code,
+                        "    ({key:?}, Entry::Plural(&[{}])),",
+                        forms.join(", ")
+                    )
+                }
+            }
+            .unwrap();
+        }
+        writeln!(code, "];").unwrap();
+    }
+    fs::write(out_dir.join("embedded_locales.rs"), code)
+        .expect("failed to write embedded_locales.rs");
+}
+
+fn parse_locale(path: &Path) -> BTreeMap<String, Entry> {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    let table: toml::Table = content
+        .parse()
+        .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display()));
+
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let entry = match value {
+                toml::Value::String(text) => Entry::Text(text),
+                toml::Value::Table(forms) => Entry::Plural(
+                    forms
+                        .into_iter()
+                        .map(|(category, text)| match text {
+                            toml::Value::String(text) => (category, text),
+                            _ => panic!("{}: `{key}.{category}` must be a string", path.display()),
+                        })
+                        .collect(),
+                ),
+                _ => panic!("{}: `{key}` must be a string or a table", path.display()),
+            };
+            (key, entry)
+        })
+        .collect()
+}
+
+/// The `{name}` placeholders in a message.
+fn placeholders(text: &str) -> BTreeSet<String> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(name, _)| name.to_owned())
+        .collect()
+}
+
+fn check_placeholders(
+    code: &str,
+    key: &str,
+    text: &str,
+    allowed: &BTreeSet<String>,
+    problems: &mut Vec<String>,
+) {
+    for name in placeholders(text).difference(allowed) {
+        problems.push(format!(
+            "{code}: `{key}` uses unknown placeholder `{{{name}}}`"
+        ));
+    }
 }
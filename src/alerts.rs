@@ -0,0 +1,338 @@
+//! Alert lead times: how long before a reminder is due its notifications go out.
+//!
+//! Offsets are whole minutes before [`Reminder::due_at`](crate::db_operations::Reminder::due_at)
+//! (the start of the day for untimed reminders). They are edited as short text such as
+//! `30d, 14d, 1d, 2h`; a bare number means days.
+
+use std::fmt;
+
+use chrono::NaiveDateTime;
+
+use crate::db_operations::{Alert, Reminder, Tag};
+
+pub const HOUR: i64 = 60;
+pub const DAY: i64 = 24 * HOUR;
+
+/// Global default until the user picks their own: 7, 3 and 1 day before.
+pub const DEFAULT_OFFSETS: [i64; 3] = [7 * DAY, 3 * DAY, DAY];
+
+/// Longest lead time accepted (about a year), so fire times stay in chrono's range.
+pub const MAX_OFFSET: i64 = 400 * DAY;
+
+/// Default lead times for a reminder with `tags`: those of its tags that have their own (all
+/// of them together), else `global`.
+pub fn default_offsets<'a>(tags: impl IntoIterator<Item = &'a Tag>, global: &[i64]) -> Vec<i64> {
+    let mut offsets: Vec<i64> = tags
+        .into_iter()
+        .filter_map(|t| t.alert_offsets.as_deref())
+        .flatten()
+        .copied()
+        .collect();
+    if offsets.is_empty() {
+        return global.to_vec();
+    }
+    offsets.sort_unstable_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    offsets
+}
+
+/// Alert state for a freshly started occurrence of a recurring reminder: every alert
+/// already reached counts as sent except the closest one, so only the most urgent is
+/// announced (instead of replaying 7->3->1 at once).
+pub fn fresh_occurrence_alerts(now: NaiveDateTime, r: &Reminder) -> Vec<Alert> {
+    let closest_reached = r
+        .alerts
+        .iter()
+        .filter(|a| r.alert_at(a) <= now)
+        .map(|a| a.offset_minutes)
+        .min();
+
+    r.alerts
+        .iter()
+        .map(|a| Alert {
+            offset_minutes: a.offset_minutes,
+            fired_at: (r.alert_at(a) <= now && Some(a.offset_minutes) != closest_reached)
+                .then_some(now),
+        })
+        .collect()
+}
+
+/// Alerts at `offsets` for `r` (already at its new date/time), keeping what `previous`
+/// knew about them: an alert that is still ahead is re-armed, one already reached keeps
+/// its state, and a newly added one that is already reached is skipped silently.
+pub fn carry_alerts(
+    now: NaiveDateTime,
+    r: &Reminder,
+    offsets: &[i64],
+    previous: &[Alert],
+) -> Vec<Alert> {
+    offsets
+        .iter()
+        .map(|&offset_minutes| {
+            let mut alert = Alert {
+                offset_minutes,
+                fired_at: None,
+            };
+            if r.alert_at(&alert) <= now {
+                alert.fired_at = match previous.iter().find(|p| p.offset_minutes == offset_minutes)
+                {
+                    Some(p) => p.fired_at,
+                    None => Some(now),
+                };
+            }
+            alert
+        })
+        .collect()
+}
+
+/// The offsets a reminder actually uses: `base` sorted from the earliest alert to the
+/// latest, without duplicates. Timed reminders always keep an at-time alert (offset 0).
+pub fn effective_offsets(base: &[i64], timed: bool) -> Vec<i64> {
+    let mut offsets: Vec<i64> = base.iter().copied().filter(|o| *o >= 0).collect();
+    if timed {
+        offsets.push(0);
+    }
+    offsets.sort_unstable_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    offsets
+}
+
+/// The user-editable part of `offsets`: drops the implicit at-time alert of timed reminders.
+pub fn user_offsets(offsets: &[i64], timed: bool) -> Vec<i64> {
+    offsets
+        .iter()
+        .copied()
+        .filter(|o| !(timed && *o == 0))
+        .collect()
+}
+
+/// Short form of one offset: `7d`, `2h` or `15m`.
+pub fn format_offset(minutes: i64) -> String {
+    if minutes != 0 && minutes % DAY == 0 {
+        format!("{}d", minutes / DAY)
+    } else if minutes != 0 && minutes % HOUR == 0 {
+        format!("{}h", minutes / HOUR)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+pub fn format_offsets(offsets: &[i64]) -> String {
+    offsets
+        .iter()
+        .map(|o| format_offset(*o))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOffsetsError(String);
+
+impl fmt::Display for ParseOffsetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid alert offset: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseOffsetsError {}
+
+/// Parses a comma/space separated list like `30d, 14d, 1d, 2h, 15m`.
+///
+/// The result is sorted from the earliest alert to the latest, without duplicates. An empty
+/// string is a valid, empty list (no advance alerts).
+pub fn parse_offsets(s: &str) -> Result<Vec<i64>, ParseOffsetsError> {
+    let mut offsets = Vec::new();
+
+    for part in s
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+    {
+        let err = || ParseOffsetsError(part.to_owned());
+        let lower = part.to_lowercase();
+        let (number, unit) = match lower.find(|c: char| !c.is_ascii_digit()) {
+            Some(i) => lower.split_at(i),
+            None => (lower.as_str(), "d"),
+        };
+
+        let value: i64 = number.parse().map_err(|_| err())?;
+        let minutes = match unit {
+            "d" => value.checked_mul(DAY),
+            "h" => value.checked_mul(HOUR),
+            "m" | "min" => Some(value),
+            _ => None,
+        }
+        .filter(|m| *m <= MAX_OFFSET)
+        .ok_or_else(err)?;
+
+        offsets.push(minutes);
+    }
+
+    Ok(effective_offsets(&offsets, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+
+    fn reminder(offsets: &[i64]) -> Reminder {
+        Reminder {
+            id: 1,
+            date: NaiveDate::from_ymd_opt(2026, 10, 14).unwrap(),
+            time: NaiveTime::from_hms_opt(10, 0, 0),
+            note: "Dentist".into(),
+            recurrence: None,
+            completed_at: None,
+            overdue_notified_on: None,
+            snoozed_until: None,
+            custom_alerts: false,
+            alerts: offsets.iter().map(|&o| alert(o, None)).collect(),
+            uid: None,
+            tags: Vec::new(),
+            priority: Default::default(),
+        }
+    }
+
+    fn alert(offset_minutes: i64, fired_at: Option<NaiveDateTime>) -> Alert {
+        Alert {
+            offset_minutes,
+            fired_at,
+        }
+    }
+
+    fn tag(alert_offsets: Option<Vec<i64>>) -> Tag {
+        Tag {
+            id: 1,
+            name: "work".into(),
+            color: [0, 0, 0],
+            alert_offsets,
+        }
+    }
+
+    #[test]
+    fn parses_offsets() {
+        assert_eq!(
+            parse_offsets("30d, 14d, 1d, 2h, 15m"),
+            Ok(vec![30 * DAY, 14 * DAY, DAY, 2 * HOUR, 15])
+        );
+        assert_eq!(parse_offsets("1 7 3"), Ok(vec![7 * DAY, 3 * DAY, DAY]));
+        assert_eq!(parse_offsets("2H;90MIN\t0m"), Ok(vec![2 * HOUR, 90, 0]));
+        assert_eq!(parse_offsets("1d, 24h, 1440m"), Ok(vec![DAY]));
+        assert_eq!(parse_offsets("400d"), Ok(vec![MAX_OFFSET]));
+        assert_eq!(parse_offsets(""), Ok(Vec::new()));
+        assert_eq!(parse_offsets(" , ;"), Ok(Vec::new()));
+
+        for invalid in [
+            "-1d",
+            "1w",
+            "d",
+            "1.5d",
+            "1 d",
+            "401d",
+            "9601h",
+            "576001m",
+            "6405119470038039d",
+            "99999999999999999999m",
+        ] {
+            assert_eq!(
+                parse_offsets(invalid),
+                Err(ParseOffsetsError(
+                    invalid.rsplit(' ').next().unwrap().into()
+                )),
+                "{invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn formats_offsets_that_parse_back() {
+        assert_eq!(format_offset(0), "0m");
+        assert_eq!(format_offset(DAY), "1d");
+        assert_eq!(format_offset(2 * HOUR), "2h");
+        assert_eq!(format_offset(25 * HOUR), "25h");
+        assert_eq!(format_offset(90), "90m");
+        assert_eq!(format_offsets(&DEFAULT_OFFSETS), "7d, 3d, 1d");
+        assert_eq!(format_offsets(&[]), "");
+
+        for offsets in [
+            vec![],
+            DEFAULT_OFFSETS.to_vec(),
+            vec![MAX_OFFSET, 25 * HOUR, 2 * HOUR, 90, 1, 0],
+        ] {
+            assert_eq!(parse_offsets(&format_offsets(&offsets)), Ok(offsets));
+        }
+    }
+
+    #[test]
+    fn effective_offsets_keep_the_at_time_alert_of_timed_reminders() {
+        let base = [HOUR, DAY, HOUR, -5];
+        assert_eq!(effective_offsets(&base, true), [DAY, HOUR, 0]);
+        assert_eq!(effective_offsets(&base, false), [DAY, HOUR]);
+        assert_eq!(effective_offsets(&[0], true), [0]);
+        assert_eq!(effective_offsets(&[], false), [] as [i64; 0]);
+
+        assert_eq!(user_offsets(&[DAY, 0], true), [DAY]);
+        assert_eq!(user_offsets(&[DAY, 0], false), [DAY, 0]);
+    }
+
+    #[test]
+    fn tags_with_lead_times_replace_the_global_defaults() {
+        let global = DEFAULT_OFFSETS;
+        assert_eq!(default_offsets(&[], &global), global);
+        assert_eq!(default_offsets(&[tag(None)], &global), global);
+        assert_eq!(default_offsets(&[tag(Some(Vec::new()))], &global), global);
+
+        let tags = [
+            tag(Some(vec![2 * HOUR, DAY])),
+            tag(None),
+            tag(Some(vec![DAY, 14 * DAY])),
+        ];
+        assert_eq!(default_offsets(&tags, &global), [14 * DAY, DAY, 2 * HOUR]);
+    }
+
+    #[test]
+    fn a_fresh_occurrence_only_announces_the_closest_alert() {
+        let r = reminder(&[7 * DAY, 3 * DAY, DAY, 0]);
+        // Two days before: the 7- and 3-day alerts are reached.
+        let now = r.due_at() - chrono::Duration::days(2);
+        assert_eq!(
+            fresh_occurrence_alerts(now, &r),
+            [
+                alert(7 * DAY, Some(now)),
+                alert(3 * DAY, None),
+                alert(DAY, None),
+                alert(0, None),
+            ]
+        );
+
+        let early = r.due_at() - chrono::Duration::days(8);
+        assert_eq!(fresh_occurrence_alerts(early, &r), r.alerts);
+    }
+
+    #[test]
+    fn carried_alerts_keep_what_was_reached() {
+        let r = reminder(&[]);
+        let now = r.due_at() - chrono::Duration::hours(12);
+        let earlier = now - chrono::Duration::days(2);
+        let previous = [
+            alert(3 * DAY, Some(earlier)),
+            alert(DAY, None),
+            alert(2 * HOUR, Some(earlier)),
+        ];
+
+        assert_eq!(
+            carry_alerts(now, &r, &[7 * DAY, 3 * DAY, DAY, 2 * HOUR, 0], &previous),
+            [
+                // New and already reached: skipped silently.
+                alert(7 * DAY, Some(now)),
+                // Reached: as before, so the pending 1-day alert still goes out.
+                alert(3 * DAY, Some(earlier)),
+                alert(DAY, None),
+                // Still ahead (e.g. after moving the reminder later): re-armed.
+                alert(2 * HOUR, None),
+                alert(0, None),
+            ]
+        );
+    }
+}
@@ -1,27 +1,51 @@
+//! Application state and UI.
+//!
+//! Split overview:
+//! - `app.rs`: `SilliReminder` state, window/tray handling, frame loop
+//! - `add_form.rs`: the "Add" section
+//! - `data.rs`: export/import and backups in Settings
+//! - `planned.rs`: the "Planned" list
+//! - `recovery.rs`: the screen shown when the database can't be opened
+//! - `tags.rs`: tags in Settings, the tag picker and chips
+//! - `notifications.rs`: boundary checks + dispatch to the notification sinks
+
+mod add_form;
+mod data;
+mod notifications;
+mod planned;
+mod recovery;
+mod tags;
+
 use std::collections::VecDeque;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 use std::{cell::RefCell, rc::Rc};
 
-use chrono::{Local, NaiveDate};
+use chrono::{Local, NaiveDate, NaiveTime};
 use eframe::egui::{self, RichText};
 use raw_window_handle::{HasWindowHandle as _, RawWindowHandle};
 use rusqlite::Connection;
 
 use crate::i18n::{self, Language};
-use crate::tray::TrayNotificationKind;
-use crate::{autostart, db_operations, settings, tray::TrayCommand};
+use crate::recurrence::Recurrence;
+use crate::settings::{self, Settings, Theme};
+use crate::sinks::Sinks;
+use crate::tray::{self, TrayCommand};
+use crate::{autostart, db_operations};
 use crate::{debug_err, debug_log};
 
 /// Main application state and UI.
 ///
 /// High-level flow:
-/// - A Win32 tray thread sends [`TrayCommand`] values over an `mpsc` channel.
+/// - The tray thread (Win32, or the SNI service on Linux) and later launches of the app
+///   (see [`crate::instance`]) send [`TrayCommand`] values over an `mpsc` channel.
 /// - `update()` drains the channel each frame and reacts:
 ///   - `Open` -> `show_window()` (un-minimize + focus)
 ///   - `Exit` -> `exit_app()` (close viewport)
+///   - `Snooze` -> `snooze_reminder()` (a notification's snooze action)
+///   - `Reload` -> re-check reminders now (the command line changed the database)
 /// - Clicking the window close button (X) does **not** exit: we cancel the close
-///   request and minimize to tray instead.
+///   request and minimize to tray instead (or exit, when there is no tray icon).
 ///
 /// Notes:
 /// - We avoid `ViewportCommand::Visible(false)` because making the window invisible can
@@ -30,139 +54,111 @@ use crate::{debug_err, debug_log};
 ///   frames; `ignore_close_frames` suppresses immediately hiding again.
 pub struct SilliReminder {
     lang: Language,
-    system_start: bool,
+    settings: Settings,
     background: bool,
     allow_close: bool,
     ignore_close_frames: u8,
     hwnd_set: bool,
+    /// What is really registered for autostart (not just what settings say); `None` if it
+    /// couldn't be read.
+    autostart_status: Option<autostart::Status>,
+    /// The user dismissed the autostart mismatch warning for this session.
+    autostart_drift_ignored: bool,
+    /// The last attempt to change autostart failed.
+    autostart_failed: bool,
     tray_rx: mpsc::Receiver<TrayCommand>,
     selected_date: NaiveDate,
+    selected_time: Option<NaiveTime>,
+    selected_recurrence: Option<Recurrence>,
+    /// Per-reminder alert override for the "Add" form; `None` uses the global defaults.
+    selected_alerts: Option<Vec<i64>>,
     note_input: String,
+    /// Tags for the "Add" form.
+    selected_tags: Vec<i64>,
+    selected_priority: db_operations::Priority,
+    /// The quick-add line (see [`crate::quick_add`]).
+    quick_input: String,
+    editing: Option<planned::EditDraft>,
+    /// Every tag, ordered by name; edited in place by Settings.
+    tags: Vec<db_operations::Tag>,
+    /// Only reminders with this tag are listed.
+    tag_filter: Option<i64>,
+    new_tag_name: String,
+    /// Why the last tag couldn't be saved, e.g. because its name is taken.
+    tag_error: Option<String>,
+    /// A checked import waiting for confirmation.
+    import_preview: Option<data::ImportPreview>,
+    data_status: Option<data::DataStatus>,
+    /// The backups offered for restoring, while that list is open.
+    backups: Option<Vec<db_operations::BackupInfo>>,
     db: Option<Rc<RefCell<Connection>>>,
+    /// Why the database couldn't be opened; while set, the window shows only that.
+    db_problem: Option<recovery::DbProblem>,
 
-    notifications: VecDeque<BoundaryNotification>,
+    notifications: VecDeque<notifications::BoundaryNotification>,
     next_boundary_check: Instant,
-}
-
-#[derive(Debug, Clone)]
-struct BoundaryNotification {
-    date: NaiveDate,
-    note: String,
-    level: u8,
+    sinks: Sinks,
 }
 
 impl SilliReminder {
-    pub fn new(system_start: bool, background: bool, tray_rx: mpsc::Receiver<TrayCommand>) -> Self {
-        let db = match db_operations::get_db() {
-            Ok(db) => Some(db),
-            Err(err) => {
-                debug_err!("failed to open database: {err}");
-                None
-            }
+    pub fn new(
+        ctx: &egui::Context,
+        settings: Settings,
+        background: bool,
+        tray_rx: mpsc::Receiver<TrayCommand>,
+    ) -> Self {
+        let (db, db_problem) = match recovery::open_db() {
+            Ok(db) => (Some(db), None),
+            Err(problem) => (None, Some(problem)),
         };
 
-        Self {
+        if let Some(db) = &db
+            && let Err(err) =
+                db_operations::backup_on_startup(&db.borrow(), settings.backup_retention)
+        {
+            debug_err!("startup backup failed: {err}");
+        }
+
+        apply_theme(ctx, settings.theme);
+        let sinks = Sinks::from_settings(&settings.notifications);
+        let autostart_status = read_autostart_status();
+
+        let mut app = Self {
             lang: i18n::language(),
-            system_start,
+            settings,
             background,
             allow_close: false,
             ignore_close_frames: 0,
             hwnd_set: false,
+            autostart_status,
+            autostart_drift_ignored: false,
+            autostart_failed: false,
             tray_rx,
             selected_date: Local::now().date_naive(),
+            selected_time: None,
+            selected_recurrence: None,
+            selected_alerts: None,
             note_input: String::new(),
+            selected_tags: Vec::new(),
+            selected_priority: db_operations::Priority::default(),
+            quick_input: String::new(),
+            editing: None,
+            tags: Vec::new(),
+            tag_filter: None,
+            new_tag_name: String::new(),
+            tag_error: None,
+            import_preview: None,
+            data_status: None,
+            backups: None,
             db,
+            db_problem,
 
             notifications: VecDeque::new(),
             next_boundary_check: Instant::now(),
-        }
-    }
-
-    fn urgency_level(today: NaiveDate, date: NaiveDate) -> u8 {
-        let days_until = (date - today).num_days();
-        if days_until <= 1 {
-            3
-        } else if days_until <= 3 {
-            2
-        } else if days_until <= 7 {
-            1
-        } else {
-            0
-        }
-    }
-
-    fn maybe_check_boundary_notifications(&mut self) {
-        let now = Instant::now();
-        if now < self.next_boundary_check {
-            return;
-        }
-
-        self.next_boundary_check = now
-            + if self.background {
-                Duration::from_secs(60)
-            } else {
-                Duration::from_secs(10)
-            };
-
-        let Some(db) = &self.db else {
-            return;
-        };
-
-        let today = Local::now().date_naive();
-        let reminders = match db_operations::list_reminders(&db.borrow()) {
-            Ok(r) => r,
-            Err(err) => {
-                debug_err!("failed to list reminders for notifications: {err}");
-                return;
-            }
+            sinks,
         };
-
-        for r in reminders {
-            let current_level = Self::urgency_level(today, r.date);
-            let previous_level = r.notified_level.min(3);
-
-            if current_level <= previous_level {
-                continue;
-            }
-
-            // Queue *each* boundary crossed so we don't skip 7->3->1 transitions
-            // even if the app was closed for a while.
-            for level in (previous_level + 1)..=current_level {
-                if level == 0 {
-                    continue;
-                }
-                self.notifications.push_back(BoundaryNotification {
-                    date: r.date,
-                    note: r.note.clone(),
-                    level,
-                });
-            }
-
-            if let Err(err) =
-                db_operations::set_reminder_notified_level(&db.borrow(), r.id, current_level)
-            {
-                debug_err!("failed to persist notified_level for {}: {err}", r.id);
-            }
-        }
-    }
-
-    fn dispatch_notifications_to_tray(&mut self) {
-        while let Some(n) = self.notifications.pop_front() {
-            let kind = match n.level {
-                1 => TrayNotificationKind::Info,
-                2 => TrayNotificationKind::Warning,
-                _ => TrayNotificationKind::Error,
-            };
-
-            let title = i18n::notif_title(self.lang, n.level);
-            let body = format!(
-                "{}\n{}: {}",
-                n.note,
-                i18n::notif_date_label(self.lang),
-                n.date
-            );
-            crate::tray::notify(&title, &body, kind);
-        }
+        app.reload_tags();
+        app
     }
 
     fn ui_main(&mut self, ctx: &egui::Context) {
@@ -180,6 +176,14 @@ impl SilliReminder {
                 });
             });
 
+        if self.db_problem.is_some() {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.vertical_centered(|ui| self.ui_header(ui));
+                self.ui_db_problem(ui);
+            });
+            return;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical(|ui| {
                 ui.vertical_centered(|ui| {
@@ -218,214 +222,191 @@ impl SilliReminder {
                 .color(accent),
         );
         ui.group(|ui| {
+            ui.horizontal(|ui| {
+                let mut enabled = self
+                    .autostart_status
+                    .as_ref()
+                    .map_or(self.settings.system_start, autostart::Status::is_registered);
+                let response = ui.checkbox(&mut enabled, i18n::ui_start_with_system(self.lang));
+
+                if response.changed() {
+                    debug_log!("system_start toggled -> {enabled}");
+                    self.set_autostart(enabled);
+                }
+
+                if self.autostart_failed {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        i18n::ui_autostart_failed(self.lang),
+                    );
+                }
+            });
+            self.ui_autostart_drift(ui);
+
             let response = ui.checkbox(
-                &mut self.system_start,
-                i18n::ui_start_with_system(self.lang),
+                &mut self.settings.overdue_renag,
+                i18n::ui_overdue_renag(self.lang),
             );
 
             if response.changed() {
-                debug_log!("system_start toggled -> {}", self.system_start);
+                debug_log!("overdue_renag toggled -> {}", self.settings.overdue_renag);
+                self.save_settings();
+            }
 
-                if let Err(err) = autostart::set_enabled(self.system_start) {
-                    debug_err!("failed to update autostart: {err}");
+            ui.horizontal_wrapped(|ui| {
+                ui.label(i18n::ui_notify_by(self.lang));
+                let before = self.settings.notifications.clone();
+                let sinks = &mut self.settings.notifications;
+                if cfg!(target_os = "windows") {
+                    ui.checkbox(&mut sinks.tray_balloon, i18n::sink_tray_balloon(self.lang));
+                    ui.checkbox(&mut sinks.message_box, i18n::sink_message_box(self.lang));
                 }
-
-                if let Err(err) = settings::save_setting(self.system_start) {
-                    debug_err!("failed to save setting: {err}");
+                if cfg!(target_os = "linux") {
+                    ui.checkbox(&mut sinks.desktop, i18n::sink_desktop(self.lang));
                 }
-            }
-        });
-    }
+                ui.checkbox(&mut sinks.log_file, i18n::sink_log_file(self.lang));
 
-    fn ui_sections(&mut self, ui: &mut egui::Ui) {
-        let accent = ui.visuals().hyperlink_color;
-        ui.label(
-            RichText::new(i18n::ui_add(self.lang))
-                .size(25.0)
-                .strong()
-                .color(accent),
-        );
-        ui.group(|ui| {
-            ui.set_min_width(ui.available_width());
-
-            let row_h = ui.spacing().interact_size.y;
-            let mut date_changed = false;
-            let mut note_changed = false;
-            let mut add_clicked = false;
+                if self.settings.notifications != before {
+                    debug_log!("notifications -> {:?}", self.settings.notifications);
+                    self.sinks = Sinks::from_settings(&self.settings.notifications);
+                    self.save_settings();
+                }
+            });
 
             ui.horizontal(|ui| {
-                let date_response: egui::Response = ui.add_sized(
-                    egui::vec2(120.0, row_h),
-                    crate::widgets::DatePickerPlButton::new(&mut self.selected_date)
-                        .id_salt("reminder_date")
-                        .format("%Y-%m-%d")
+                ui.label(i18n::ui_default_alerts(self.lang));
+                let response = ui.add(
+                    crate::widgets::AlertsInput::new(&mut self.settings.alert_offsets)
+                        .id_salt("default_alerts")
                         .language(self.lang),
                 );
-                date_changed = date_response.changed();
-
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    add_clicked = ui
-                        .add_sized(
-                            egui::vec2(70.0, row_h),
-                            egui::Button::new(i18n::ui_add_button(self.lang)),
-                        )
-                        .clicked();
-
-                    let note_response: egui::Response = ui.add_sized(
-                        egui::vec2(ui.available_width(), row_h),
-                        egui::TextEdit::singleline(&mut self.note_input)
-                            .id_salt("note_input")
-                            .hint_text(i18n::ui_note_hint(self.lang)),
-                    );
-                    note_changed = note_response.changed();
-                });
+
+                if response.changed() {
+                    debug_log!("alert_offsets -> {:?}", self.settings.alert_offsets);
+                    self.save_settings();
+                    self.apply_default_alerts();
+                }
             });
 
-            if add_clicked {
-                if let Some(db) = &self.db {
-                    let note = self.note_input.trim();
-                    if note.is_empty() {
-                        debug_err!("note is empty; nothing inserted");
-                    } else {
-                        match db_operations::insert_reminder(&db.borrow(), self.selected_date, note)
-                        {
-                            Ok(id) => {
-                                debug_log!("Dodano #{id}: {}, {}", self.selected_date, note);
-                                self.note_input.clear();
-                            }
-                            Err(err) => debug_err!("failed to insert reminder: {err}"),
+            self.ui_tags(ui);
+
+            ui.horizontal(|ui| {
+                ui.label(i18n::ui_language(self.lang));
+                let before = self.settings.language;
+                let name = |choice: Option<Language>| match choice {
+                    Some(lang) => lang.native_name().to_owned(),
+                    None => format!(
+                        "{} ({})",
+                        i18n::ui_language_system(self.lang),
+                        i18n::detect_language().native_name()
+                    ),
+                };
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(name(self.settings.language))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.settings.language, None, name(None));
+                        for lang in Language::ALL {
+                            ui.selectable_value(
+                                &mut self.settings.language,
+                                Some(lang),
+                                name(Some(lang)),
+                            );
                         }
-                    }
-                } else {
-                    debug_err!("database not available");
+                    });
+
+                if self.settings.language != before {
+                    debug_log!("language -> {:?}", self.settings.language);
+                    i18n::set_language(self.settings.language);
+                    self.lang = i18n::language();
+                    tray::refresh();
+                    self.save_settings();
                 }
-            }
+            });
 
-            if date_changed {
-                debug_log!("Selected date -> {}", self.selected_date);
-            }
+            ui.horizontal(|ui| {
+                ui.label(i18n::ui_theme(self.lang));
+                let before = self.settings.theme;
+                egui::ComboBox::from_id_salt("theme")
+                    .selected_text(i18n::theme_name(self.lang, self.settings.theme))
+                    .show_ui(ui, |ui| {
+                        for theme in Theme::ALL {
+                            ui.selectable_value(
+                                &mut self.settings.theme,
+                                theme,
+                                i18n::theme_name(self.lang, theme),
+                            );
+                        }
+                    });
 
-            if note_changed {
-                debug_log!("Note -> {}", self.note_input);
-            }
+                if self.settings.theme != before {
+                    debug_log!("theme -> {:?}", self.settings.theme);
+                    apply_theme(ui.ctx(), self.settings.theme);
+                    self.save_settings();
+                }
+            });
+
+            self.ui_data(ui);
         });
     }
 
-    fn ui_planed(&mut self, ui: &mut egui::Ui) {
-        let accent = ui.visuals().hyperlink_color;
-        ui.label(
-            RichText::new(i18n::ui_planned(self.lang))
-                .size(28.0)
-                .strong()
-                .color(accent),
-        );
-        ui.group(|ui| {
-            ui.set_min_size(ui.available_size());
+    /// Registers or removes autostart, then shows what the system actually ended up with.
+    fn set_autostart(&mut self, enabled: bool) {
+        let result = autostart::set_enabled(enabled, self.settings.autostart_method);
+        if let Err(err) = &result {
+            debug_err!("failed to update autostart: {err}");
+        }
 
-            ui.vertical(|ui| {
-                let Some(db) = &self.db else {
-                    ui.label(i18n::ui_no_db(self.lang));
-                    return;
-                };
+        self.autostart_status = read_autostart_status();
+        let registered = match &self.autostart_status {
+            Some(status) => status.is_registered(),
+            None => enabled && result.is_ok(),
+        };
+        self.autostart_failed = result.is_err() || registered != enabled;
+        self.autostart_drift_ignored = false;
 
-                match db_operations::list_reminders(&db.borrow()) {
-                    Ok(reminders) => {
-                        if reminders.is_empty() {
-                            ui.label(i18n::ui_empty(self.lang));
-                        } else {
-                            let mut delete_id: Option<i64> = None;
-
-                            egui::ScrollArea::vertical()
-                                .max_height(ui.available_height())
-                                .auto_shrink([false, false])
-                                .show(ui, |ui| {
-                                    for r in reminders.iter() {
-                                        ui.push_id(r.id, |ui| {
-                                            egui::Frame::NONE
-                                                .fill(ui.visuals().faint_bg_color)
-                                                .stroke(
-                                                    ui.visuals().widgets.noninteractive.bg_stroke,
-                                                )
-                                                .corner_radius(egui::CornerRadius::same(6))
-                                                .inner_margin(egui::Margin::symmetric(8, 6))
-                                                .show(ui, |ui| {
-                                                    ui.horizontal(|ui| {
-                                                        let text_size = 18.0;
-                                                        let row_h = ui
-                                                            .spacing()
-                                                            .interact_size
-                                                            .y
-                                                            .max(text_size + 10.0);
-
-                                                        let today = Local::now().date_naive();
-                                                        let days_until =
-                                                            (r.date - today).num_days();
-                                                        let reminder_color = if days_until > 7 {
-                                                            ui.visuals().text_color()
-                                                        } else if days_until > 3 {
-                                                            ui.visuals().hyperlink_color
-                                                        } else if days_until > 1 {
-                                                            ui.visuals().warn_fg_color
-                                                        } else {
-                                                            ui.visuals().error_fg_color
-                                                        };
-
-                                                        let row_text = RichText::new(format!(
-                                                            "{}  -  {}",
-                                                            r.date, r.note
-                                                        ))
-                                                        .size(text_size)
-                                                        .color(reminder_color);
-                                                        ui.label(row_text);
-
-                                                        let remaining = ui.available_width();
-                                                        ui.allocate_ui_with_layout(
-                                                            egui::vec2(remaining, 0.0),
-                                                            egui::Layout::right_to_left(
-                                                                egui::Align::Center,
-                                                            ),
-                                                            |ui| {
-                                                                let danger =
-                                                                    ui.visuals().error_fg_color;
-                                                                let x = egui::Button::new(
-                                                                    RichText::new("X")
-                                                                        .size(22.0)
-                                                                        .color(danger),
-                                                                );
-                                                                if ui
-                                                                    .add_sized(
-                                                                        egui::vec2(36.0, row_h),
-                                                                        x,
-                                                                    )
-                                                                    .clicked()
-                                                                {
-                                                                    delete_id = Some(r.id);
-                                                                }
-                                                            },
-                                                        );
-                                                    });
-                                                });
-                                            ui.add_space(4.0);
-                                        });
-                                    }
-                                });
-
-                            if let Some(id) = delete_id {
-                                if let Err(err) = db_operations::delete_reminder(&db.borrow(), id) {
-                                    debug_err!("failed to delete reminder {id}: {err}");
-                                }
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        ui.label(i18n::ui_db_read_error(self.lang));
-                        debug_err!("failed to list reminders: {err}");
-                    }
-                }
-            });
+        self.settings.system_start = registered;
+        self.save_settings();
+    }
+
+    /// Warns when the registered autostart entry doesn't match the setting (e.g. the entry
+    /// was removed by another tool, or the EXE moved since it was written) and offers to
+    /// rewrite it from the setting.
+    fn ui_autostart_drift(&mut self, ui: &mut egui::Ui) {
+        if self.autostart_drift_ignored {
+            return;
+        }
+
+        let message = match (&self.autostart_status, self.settings.system_start) {
+            (Some(autostart::Status::PointsElsewhere(path)), _) => {
+                i18n::ui_autostart_elsewhere(self.lang, &path.display().to_string())
+            }
+            (Some(autostart::Status::Absent), true) => {
+                i18n::ui_autostart_missing(self.lang).to_owned()
+            }
+            (Some(autostart::Status::Present), false) => {
+                i18n::ui_autostart_unexpected(self.lang).to_owned()
+            }
+            _ => return,
+        };
+
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(ui.visuals().warn_fg_color, message);
+            if ui.button(i18n::ui_autostart_repair(self.lang)).clicked() {
+                debug_log!("repairing autostart -> {}", self.settings.system_start);
+                self.set_autostart(self.settings.system_start);
+            }
+            if ui.button(i18n::ui_autostart_ignore(self.lang)).clicked() {
+                self.autostart_drift_ignored = true;
+            }
         });
     }
 
+    fn save_settings(&self) {
+        if let Err(err) = settings::save(&self.settings) {
+            debug_err!("failed to save settings: {err}");
+        }
+    }
+
     fn hide_to_tray(&mut self, ctx: &egui::Context) {
         self.background = true;
         ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
@@ -461,13 +442,12 @@ impl eframe::App for SilliReminder {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Capture the native window handle once so the tray thread can restore the window
         // even if egui isn't getting repaints while minimized.
-        if !self.hwnd_set {
-            if let Ok(handle) = frame.window_handle() {
-                if let RawWindowHandle::Win32(win32) = handle.as_raw() {
-                    crate::tray::set_main_window_hwnd(win32.hwnd.get() as isize);
-                    self.hwnd_set = true;
-                }
-            }
+        if !self.hwnd_set
+            && let Ok(handle) = frame.window_handle()
+            && let RawWindowHandle::Win32(win32) = handle.as_raw()
+        {
+            tray::set_main_window_hwnd(win32.hwnd.get());
+            self.hwnd_set = true;
         }
 
         let commands: Vec<TrayCommand> = self.tray_rx.try_iter().collect();
@@ -475,12 +455,24 @@ impl eframe::App for SilliReminder {
             match cmd {
                 TrayCommand::Open => self.show_window(ctx),
                 TrayCommand::Exit => self.exit_app(ctx),
+                TrayCommand::Snooze { id, minutes } => {
+                    let until =
+                        Local::now().naive_local() + chrono::Duration::minutes(i64::from(minutes));
+                    self.snooze_reminder(id, Some(until));
+                }
+                TrayCommand::Reload => self.next_boundary_check = Instant::now(),
             }
         }
 
         // Boundary notifications (DB-backed): check periodically even in background.
         self.maybe_check_boundary_notifications();
-        self.dispatch_notifications_to_tray();
+        self.dispatch_notifications();
+        // egui only repaints on input while the window is open; make sure the next
+        // check (e.g. a reminder's exact time) still runs on schedule.
+        ctx.request_repaint_after(
+            self.next_boundary_check
+                .saturating_duration_since(Instant::now()),
+        );
 
         // Close button (X): keep running and hide to tray.
         if self.ignore_close_frames > 0 {
@@ -494,7 +486,12 @@ impl eframe::App for SilliReminder {
             // Only treat it as an actual "user pressed X" when we're not in the
             // post-open suppression window.
             if self.ignore_close_frames == 0 {
-                self.hide_to_tray(ctx);
+                if tray::is_available() {
+                    self.hide_to_tray(ctx);
+                } else {
+                    // Nowhere to hide to (e.g. no tray host on this desktop).
+                    self.exit_app(ctx);
+                }
             }
         }
 
@@ -509,3 +506,17 @@ impl eframe::App for SilliReminder {
         self.ui_main(ctx);
     }
 }
+
+fn read_autostart_status() -> Option<autostart::Status> {
+    autostart::status()
+        .map_err(|err| debug_err!("failed to read autostart state: {err}"))
+        .ok()
+}
+
+fn apply_theme(ctx: &egui::Context, theme: Theme) {
+    ctx.set_theme(match theme {
+        Theme::System => egui::ThemePreference::System,
+        Theme::Light => egui::ThemePreference::Light,
+        Theme::Dark => egui::ThemePreference::Dark,
+    });
+}
@@ -0,0 +1,342 @@
+use std::error::Error;
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use eframe::egui::{self, RichText};
+
+use super::SilliReminder;
+use crate::db_operations::{self, Alert, Priority};
+use crate::i18n::{self, Language};
+use crate::recurrence::Recurrence;
+use crate::{debug_err, debug_log, quick_add};
+
+impl SilliReminder {
+    pub(super) fn ui_sections(&mut self, ui: &mut egui::Ui) {
+        let accent = ui.visuals().hyperlink_color;
+        ui.label(
+            RichText::new(i18n::ui_add(self.lang))
+                .size(25.0)
+                .strong()
+                .color(accent),
+        );
+        ui.group(|ui| {
+            ui.set_min_width(ui.available_width());
+
+            let row_h = ui.spacing().interact_size.y;
+            self.ui_quick_add(ui, row_h);
+            ui.separator();
+
+            let mut date_changed = false;
+            let mut time_changed = false;
+            let mut note_changed = false;
+            let mut add_clicked = false;
+
+            ui.horizontal(|ui| {
+                let date_response: egui::Response = ui.add_sized(
+                    egui::vec2(120.0, row_h),
+                    crate::widgets::DatePickerPlButton::new(&mut self.selected_date)
+                        .id_salt("reminder_date")
+                        .format("%Y-%m-%d")
+                        .language(self.lang),
+                );
+                date_changed = date_response.changed();
+
+                let time_response: egui::Response = ui.add(
+                    crate::widgets::TimeInput::new(&mut self.selected_time)
+                        .id_salt("reminder_time")
+                        .language(self.lang),
+                );
+                time_changed = time_response.changed();
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    add_clicked = ui
+                        .add_sized(
+                            egui::vec2(70.0, row_h),
+                            egui::Button::new(i18n::ui_add_button(self.lang)),
+                        )
+                        .clicked();
+
+                    let note_response: egui::Response = ui.add_sized(
+                        egui::vec2(ui.available_width(), row_h),
+                        egui::TextEdit::singleline(&mut self.note_input)
+                            .id_salt("note_input")
+                            .hint_text(i18n::ui_note_hint(self.lang)),
+                    );
+                    note_changed = note_response.changed();
+                });
+            });
+
+            let recurrence_changed = ui
+                .add(
+                    crate::widgets::RecurrenceInput::new(
+                        &mut self.selected_recurrence,
+                        self.selected_date,
+                    )
+                    .id_salt("reminder_recurrence")
+                    .language(self.lang),
+                )
+                .changed();
+
+            let priority_changed = ui_priority(
+                ui,
+                self.lang,
+                "reminder_priority",
+                &mut self.selected_priority,
+            );
+
+            let tags_changed = !self.tags.is_empty()
+                && super::tags::ui_tag_picker(ui, &self.tags, &mut self.selected_tags);
+
+            let defaults = self.default_offsets(&self.selected_tags);
+            let alerts_changed = ui_alerts_override(
+                ui,
+                self.lang,
+                "reminder_alerts",
+                &mut self.selected_alerts,
+                &defaults,
+            );
+
+            if add_clicked {
+                let note = self.note_input.trim().to_owned();
+                let recurrence = self.selected_recurrence.take();
+                let alerts = self.selected_alerts.take();
+                let tags = self.selected_tags.clone();
+                let added = self.add_reminder(
+                    self.selected_date,
+                    self.selected_time,
+                    &note,
+                    recurrence.as_ref(),
+                    self.selected_priority,
+                    alerts.as_deref(),
+                    &tags,
+                );
+                if added {
+                    self.note_input.clear();
+                    self.selected_priority = Priority::default();
+                } else {
+                    // Keep the form as it was so nothing has to be re-entered.
+                    self.selected_recurrence = recurrence;
+                    self.selected_alerts = alerts;
+                }
+            }
+
+            if date_changed {
+                debug_log!("Selected date -> {}", self.selected_date);
+            }
+
+            if time_changed {
+                debug_log!("Selected time -> {:?}", self.selected_time);
+            }
+
+            if recurrence_changed {
+                debug_log!("Recurrence -> {:?}", self.selected_recurrence);
+            }
+
+            if priority_changed {
+                debug_log!("Priority -> {:?}", self.selected_priority);
+            }
+
+            if tags_changed {
+                debug_log!("Tags -> {:?}", self.selected_tags);
+            }
+
+            if alerts_changed {
+                debug_log!("Alerts -> {:?}", self.selected_alerts);
+            }
+
+            if note_changed {
+                debug_log!("Note -> {}", self.note_input);
+            }
+        });
+    }
+}
+
+impl SilliReminder {
+    /// One-line entry such as "jutro 9:00 dentysta", parsed by [`quick_add`] and previewed
+    /// below the field before it is saved.
+    fn ui_quick_add(&mut self, ui: &mut egui::Ui, row_h: f32) {
+        let mut submitted = false;
+        ui.horizontal(|ui| {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                submitted = ui
+                    .add_sized(
+                        egui::vec2(70.0, row_h),
+                        egui::Button::new(i18n::ui_add_button(self.lang)),
+                    )
+                    .clicked();
+
+                let response = ui.add_sized(
+                    egui::vec2(ui.available_width(), row_h),
+                    egui::TextEdit::singleline(&mut self.quick_input)
+                        .id_salt("quick_input")
+                        .hint_text(i18n::ui_quick_add_hint(self.lang)),
+                );
+                submitted |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            });
+        });
+
+        if self.quick_input.trim().is_empty() {
+            return;
+        }
+
+        let parsed = quick_add::parse(&self.quick_input, Local::now().naive_local());
+        let preview = match parsed.date {
+            _ if parsed.note.is_empty() => Err(i18n::ui_quick_add_no_note(self.lang)),
+            None => Err(i18n::ui_quick_add_no_date(self.lang)),
+            Some(date) => Ok(date),
+        };
+
+        match preview {
+            Ok(date) => {
+                let when = match parsed.time {
+                    Some(time) => format!("{date} {}", time.format("%H:%M")),
+                    None => date.to_string(),
+                };
+                ui.weak(format!("→ {when}  -  {}", parsed.note));
+                if submitted
+                    && self.add_reminder(
+                        date,
+                        parsed.time,
+                        &parsed.note,
+                        None,
+                        Priority::default(),
+                        None,
+                        &[],
+                    )
+                {
+                    self.quick_input.clear();
+                }
+            }
+            Err(problem) => {
+                ui.colored_label(ui.visuals().warn_fg_color, problem);
+            }
+        }
+    }
+
+    /// Inserts a reminder with `custom_alerts` (or the default lead times of its tags).
+    /// Returns whether it was saved.
+    #[allow(clippy::too_many_arguments)]
+    fn add_reminder(
+        &mut self,
+        date: NaiveDate,
+        time: Option<NaiveTime>,
+        note: &str,
+        recurrence: Option<&Recurrence>,
+        priority: Priority,
+        custom_alerts: Option<&[i64]>,
+        tags: &[i64],
+    ) -> bool {
+        let Some(db) = &self.db else {
+            debug_err!("database not available");
+            return false;
+        };
+        if note.is_empty() {
+            debug_err!("note is empty; nothing inserted");
+            return false;
+        }
+
+        let alerts: Vec<Alert> = self
+            .alert_offsets(custom_alerts, tags, time.is_some())
+            .into_iter()
+            .map(|offset_minutes| Alert {
+                offset_minutes,
+                fired_at: None,
+            })
+            .collect();
+
+        let conn = db.borrow();
+        let insert = || -> Result<i64, Box<dyn Error>> {
+            let tx = conn.unchecked_transaction()?;
+            let id = db_operations::insert_reminder(
+                &tx,
+                date,
+                time,
+                note,
+                recurrence,
+                custom_alerts.is_some(),
+                &alerts,
+            )?;
+            db_operations::set_reminder_priority(&tx, id, priority)?;
+            db_operations::set_reminder_tags(&tx, id, tags)?;
+            tx.commit()?;
+            Ok(id)
+        };
+        let inserted = insert();
+        drop(conn);
+
+        match inserted {
+            Ok(id) => {
+                debug_log!("Dodano #{id}: {date}, {note}");
+                // Pick up the new reminder (and its exact time) right away.
+                self.next_boundary_check = std::time::Instant::now();
+                true
+            }
+            Err(err) => {
+                debug_err!("failed to insert reminder: {err}");
+                false
+            }
+        }
+    }
+}
+
+/// "Own alerts" toggle plus its lead times; `None` means the reminder follows `defaults`.
+/// Returns whether the selection changed.
+pub(super) fn ui_alerts_override(
+    ui: &mut egui::Ui,
+    lang: Language,
+    id_salt: &str,
+    alerts: &mut Option<Vec<i64>>,
+    defaults: &[i64],
+) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        let mut custom = alerts.is_some();
+        if ui
+            .checkbox(&mut custom, i18n::ui_custom_alerts(lang))
+            .changed()
+        {
+            *alerts = custom.then(|| defaults.to_vec());
+            changed = true;
+        }
+
+        match alerts {
+            Some(offsets) => {
+                changed |= ui
+                    .add(
+                        crate::widgets::AlertsInput::new(offsets)
+                            .id_salt(id_salt)
+                            .language(lang),
+                    )
+                    .changed();
+            }
+            None => {
+                ui.weak(crate::alerts::format_offsets(defaults));
+            }
+        }
+    });
+
+    changed
+}
+
+/// Priority drop-down. Returns whether it changed.
+pub(super) fn ui_priority(
+    ui: &mut egui::Ui,
+    lang: Language,
+    id_salt: &str,
+    priority: &mut Priority,
+) -> bool {
+    let before = *priority;
+
+    ui.horizontal(|ui| {
+        ui.label(i18n::ui_priority(lang));
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(i18n::priority_name(lang, *priority))
+            .show_ui(ui, |ui| {
+                for p in Priority::ALL {
+                    ui.selectable_value(priority, p, i18n::priority_name(lang, p));
+                }
+            });
+    });
+
+    *priority != before
+}
@@ -0,0 +1,346 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use eframe::egui;
+use rusqlite::Connection;
+
+use super::SilliReminder;
+use crate::db_operations::{self, BackupInfo};
+use crate::i18n;
+use crate::transfer::{self, Filter, Format, ImportOptions, ImportReport, Item};
+use crate::{debug_err, debug_log};
+
+/// How many invalid lines the import preview lists before summing up the rest.
+const MAX_SHOWN_ERRORS: usize = 5;
+
+/// An import that went through a dry run and waits for the user to confirm it.
+pub(super) struct ImportPreview {
+    items: Vec<Item>,
+    options: ImportOptions,
+    report: ImportReport,
+    errors: Vec<String>,
+}
+
+/// The outcome of the last export or import, shown under the buttons.
+pub(super) enum DataStatus {
+    Done(String),
+    Failed(String),
+}
+
+impl SilliReminder {
+    /// The "Data" rows of Settings: export to and import from CSV, JSON and iCalendar files,
+    /// and the automatic backups.
+    pub(super) fn ui_data(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n::ui_data(self.lang));
+            if ui.button(i18n::ui_export(self.lang)).clicked() {
+                self.export_data();
+            }
+            if ui.button(i18n::ui_import(self.lang)).clicked() {
+                self.preview_import();
+            }
+        });
+
+        self.ui_import_preview(ui);
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n::ui_backups_keep(self.lang));
+            let response =
+                ui.add(egui::DragValue::new(&mut self.settings.backup_retention).range(1..=100));
+            if response.changed() {
+                debug_log!("backup_retention -> {}", self.settings.backup_retention);
+                self.save_settings();
+            }
+
+            if ui.button(i18n::ui_restore_backup(self.lang)).clicked() {
+                self.toggle_backups();
+            }
+        });
+
+        self.ui_backups(ui);
+        self.ui_data_status(ui);
+    }
+
+    /// The outcome of the last export, import, restore or recovery.
+    pub(super) fn ui_data_status(&self, ui: &mut egui::Ui) {
+        match &self.data_status {
+            Some(DataStatus::Done(message)) => {
+                ui.label(message);
+            }
+            Some(DataStatus::Failed(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, message);
+            }
+            None => {}
+        }
+    }
+
+    fn ui_import_preview(&mut self, ui: &mut egui::Ui) {
+        let Some(preview) = &self.import_preview else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        ui.group(|ui| {
+            let report = &preview.report;
+            ui.label(i18n::ui_import_ready(self.lang, report.imported));
+            if report.duplicates + report.past + preview.errors.len() > 0 {
+                ui.label(i18n::ui_import_skipped(
+                    self.lang,
+                    report.duplicates,
+                    report.past,
+                    preview.errors.len(),
+                ));
+            }
+            for rule in &report.unsupported_rules {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    i18n::ui_import_unsupported_rule(self.lang, rule),
+                );
+            }
+            for err in preview.errors.iter().take(MAX_SHOWN_ERRORS) {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+            if preview.errors.len() > MAX_SHOWN_ERRORS {
+                ui.label(i18n::ui_import_more_errors(
+                    self.lang,
+                    preview.errors.len() - MAX_SHOWN_ERRORS,
+                ));
+            }
+
+            ui.horizontal(|ui| {
+                confirmed = ui
+                    .add_enabled(
+                        report.imported > 0,
+                        egui::Button::new(i18n::ui_import_confirm(self.lang)),
+                    )
+                    .clicked();
+                cancelled = ui.button(i18n::ui_cancel(self.lang)).clicked();
+            });
+        });
+
+        if confirmed {
+            self.run_import();
+        } else if cancelled {
+            self.import_preview = None;
+        }
+    }
+
+    /// Asks where to save, then writes every reminder (active and done) in the format of
+    /// the chosen extension (CSV when there is none).
+    fn export_data(&mut self) {
+        let Some(path) = file_dialog().set_file_name("SilliReminder.csv").save_file() else {
+            return;
+        };
+        let (path, format) = match Format::from_path(&path) {
+            Some(format) => (path, format),
+            None => (path.with_extension("csv"), Format::Csv),
+        };
+
+        let filter = Filter {
+            all: true,
+            ..Filter::default()
+        };
+        let result = self
+            .with_db(|conn| transfer::export(conn, &filter, format))
+            .and_then(|(text, count)| {
+                std::fs::write(&path, text)?;
+                Ok(count)
+            });
+
+        self.data_status = Some(match result {
+            Ok(count) => {
+                debug_log!("exported {count} reminders to {}", path.display());
+                DataStatus::Done(i18n::ui_exported(
+                    self.lang,
+                    count,
+                    &path.display().to_string(),
+                ))
+            }
+            Err(err) => {
+                debug_err!("export failed: {err}");
+                DataStatus::Failed(i18n::ui_export_failed(self.lang, &err.to_string()))
+            }
+        });
+    }
+
+    /// Asks for a file, reads it and does a dry run, so the user sees what would be
+    /// imported (and which lines are invalid) before anything changes.
+    fn preview_import(&mut self) {
+        let Some(path) = file_dialog().pick_file() else {
+            return;
+        };
+        self.import_preview = None;
+        self.data_status = None;
+
+        match self.dry_run_import(path) {
+            Ok(preview) => self.import_preview = Some(preview),
+            Err(err) => {
+                debug_err!("import failed: {err}");
+                self.data_status = Some(DataStatus::Failed(i18n::ui_import_failed(
+                    self.lang,
+                    &err.to_string(),
+                )));
+            }
+        }
+    }
+
+    fn dry_run_import(&self, path: PathBuf) -> Result<ImportPreview, Box<dyn Error>> {
+        let format = Format::from_path(&path).ok_or("unknown file type")?;
+        let parsed = transfer::parse(&std::fs::read_to_string(&path)?, format)?;
+
+        // Calendar files hold past events one wouldn't want reminders for; CSV and JSON
+        // files are reminder data and come back as they were.
+        let options = ImportOptions {
+            keep_past: !matches!(format, Format::Ical(_)),
+            dry_run: false,
+        };
+        let dry_run = ImportOptions {
+            dry_run: true,
+            ..options
+        };
+        let report = self.with_db(|conn| {
+            transfer::import(conn, parsed.items.clone(), &self.settings, dry_run)
+        })?;
+
+        Ok(ImportPreview {
+            items: parsed.items,
+            options,
+            report,
+            errors: parsed.errors,
+        })
+    }
+
+    fn run_import(&mut self) {
+        let Some(preview) = self.import_preview.take() else {
+            return;
+        };
+
+        let result = self
+            .with_db(|conn| transfer::import(conn, preview.items, &self.settings, preview.options));
+        self.data_status = Some(match result {
+            Ok(report) => {
+                debug_log!("imported {} reminders", report.imported);
+                // Pick up the new reminders right away.
+                self.next_boundary_check = std::time::Instant::now();
+                DataStatus::Done(i18n::ui_imported(self.lang, report.imported))
+            }
+            Err(err) => {
+                debug_err!("import failed: {err}");
+                DataStatus::Failed(i18n::ui_import_failed(self.lang, &err.to_string()))
+            }
+        });
+    }
+
+    pub(super) fn toggle_backups(&mut self) {
+        if self.backups.take().is_some() {
+            return;
+        }
+        self.data_status = None;
+        match db_operations::list_backups() {
+            Ok(backups) => self.backups = Some(backups),
+            Err(err) => {
+                debug_err!("failed to list backups: {err}");
+                self.data_status = Some(DataStatus::Failed(i18n::ui_restore_failed(
+                    self.lang,
+                    &err.to_string(),
+                )));
+            }
+        }
+    }
+
+    /// The backups, newest first, each with when and why it was made and what it holds.
+    pub(super) fn ui_backups(&mut self, ui: &mut egui::Ui) {
+        let Some(backups) = &self.backups else {
+            return;
+        };
+
+        let mut chosen = None;
+        ui.group(|ui| {
+            if backups.is_empty() {
+                ui.label(i18n::ui_no_backups(self.lang));
+            }
+            egui::ScrollArea::vertical()
+                .id_salt("backups")
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    for backup in backups {
+                        ui.horizontal(|ui| {
+                            let restore = ui.add_enabled(
+                                backup.reminders.is_some() && !backup.too_new,
+                                egui::Button::new(i18n::ui_restore(self.lang)),
+                            );
+                            if restore.clicked() {
+                                chosen = Some(backup.clone());
+                            }
+                            let label = ui.label(backup_label(self.lang, backup));
+                            if backup.too_new {
+                                label.on_hover_text(i18n::ui_backup_too_new(self.lang));
+                            }
+                        });
+                    }
+                });
+        });
+
+        if let Some(backup) = chosen {
+            self.restore(&backup);
+        }
+    }
+
+    fn restore(&mut self, backup: &BackupInfo) {
+        let result = match self.db.clone() {
+            Some(db) => db_operations::restore_backup(&mut db.borrow_mut(), &backup.path),
+            // The database can't be opened; put the backup in its place and try again.
+            None => db_operations::restore_backup_file(&backup.path).and_then(|()| {
+                self.reopen_db();
+                match &self.db_problem {
+                    Some(problem) => Err(problem.to_string().into()),
+                    None => Ok(()),
+                }
+            }),
+        };
+
+        self.backups = None;
+        self.data_status = Some(match result {
+            Ok(()) => {
+                // Whatever was on screen belongs to the replaced data.
+                self.editing = None;
+                self.import_preview = None;
+                self.reload_tags();
+                self.next_boundary_check = std::time::Instant::now();
+                DataStatus::Done(i18n::ui_restored(
+                    self.lang,
+                    &backup.created.format("%Y-%m-%d %H:%M").to_string(),
+                ))
+            }
+            Err(err) => {
+                debug_err!("restore failed: {err}");
+                DataStatus::Failed(i18n::ui_restore_failed(self.lang, &err.to_string()))
+            }
+        });
+    }
+
+    fn with_db<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn Error>>,
+    ) -> Result<T, Box<dyn Error>> {
+        let db = self.db.as_ref().ok_or("database not available")?;
+        f(&db.borrow())
+    }
+}
+
+fn backup_label(lang: i18n::Language, backup: &BackupInfo) -> String {
+    let created = backup.created.format("%Y-%m-%d %H:%M").to_string();
+    let kind = i18n::backup_kind_name(lang, backup.kind);
+    match backup.reminders {
+        Some(count) => i18n::ui_backup_entry(lang, &created, kind, count),
+        None => i18n::ui_backup_unreadable(lang, &created, kind),
+    }
+}
+
+fn file_dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"])
+        .add_filter("iCalendar", &["ics"])
+}
@@ -0,0 +1,386 @@
+use std::time::{Duration, Instant};
+
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::Connection;
+
+use super::SilliReminder;
+use crate::alerts;
+use crate::db_operations::{self, Priority, Reminder};
+use crate::debug_err;
+use crate::i18n;
+use crate::sinks::Notification;
+use crate::tray::TrayNotificationKind;
+
+#[derive(Debug, Clone, Copy)]
+enum NotificationEvent {
+    /// An alert `offset_minutes` before the due time went out (0: at the due time).
+    Alert {
+        offset_minutes: i64,
+        kind: TrayNotificationKind,
+    },
+    /// Past its due day, by this many days.
+    Overdue(i64),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BoundaryNotification {
+    id: i64,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    note: String,
+    priority: Priority,
+    event: NotificationEvent,
+}
+
+impl SilliReminder {
+    /// How loud the alert `offset_minutes` before `r` is, by its place among the reminder's
+    /// own lead times: the closest one (or the at-time alert) is an error, the next a
+    /// warning, anything earlier just info. With 7/3/1 days this keeps the classic
+    /// blue/orange/red progression.
+    pub(super) fn alert_severity(r: &Reminder, offset_minutes: i64) -> TrayNotificationKind {
+        if offset_minutes == 0 {
+            return TrayNotificationKind::Error;
+        }
+
+        let closer = r
+            .alerts
+            .iter()
+            .filter(|a| a.offset_minutes > 0 && a.offset_minutes < offset_minutes)
+            .count();
+        match closer {
+            0 => TrayNotificationKind::Error,
+            1 => TrayNotificationKind::Warning,
+            _ => TrayNotificationKind::Info,
+        }
+    }
+
+    /// Urgency of a reminder at `now`: the severity of the closest alert already reached,
+    /// or `None` while none is.
+    pub(super) fn urgency(now: NaiveDateTime, r: &Reminder) -> Option<TrayNotificationKind> {
+        r.alerts
+            .iter()
+            .filter(|a| r.alert_at(a) <= now)
+            .map(|a| a.offset_minutes)
+            .min()
+            .map(|offset| Self::alert_severity(r, offset))
+    }
+
+    /// Whether the current occurrence of `r` is over: an untimed reminder once its day has
+    /// ended, a timed one once it is due and all its alerts went out.
+    fn occurrence_passed(now: NaiveDateTime, r: &Reminder) -> bool {
+        match r.time {
+            None => r.date < now.date(),
+            Some(_) => now >= r.due_at() && r.alerts.iter().all(|a| a.fired_at.is_some()),
+        }
+    }
+
+    /// Offsets a reminder gets from an optional per-reminder override, else the defaults
+    /// for its tags (see [`SilliReminder::default_offsets`]).
+    pub(super) fn alert_offsets(
+        &self,
+        custom: Option<&[i64]>,
+        tag_ids: &[i64],
+        timed: bool,
+    ) -> Vec<i64> {
+        match custom {
+            Some(custom) => alerts::effective_offsets(custom, timed),
+            None => alerts::effective_offsets(&self.default_offsets(tag_ids), timed),
+        }
+    }
+
+    /// Re-applies the default lead times (global or per tag) to every reminder without its
+    /// own alerts.
+    pub(super) fn apply_default_alerts(&mut self) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let conn = db.borrow();
+
+        let reminders = match db_operations::list_reminders(&conn) {
+            Ok(r) => r,
+            Err(err) => {
+                debug_err!("failed to list reminders for default alerts: {err}");
+                return;
+            }
+        };
+
+        let now = Local::now().naive_local();
+        for r in reminders.iter().filter(|r| !r.custom_alerts) {
+            let tag_ids: Vec<i64> = r.tags.iter().map(|t| t.id).collect();
+            let offsets = self.alert_offsets(None, &tag_ids, r.time.is_some());
+            let alerts = alerts::carry_alerts(now, r, &offsets, &r.alerts);
+            if alerts == r.alerts {
+                continue;
+            }
+            if let Err(err) = db_operations::set_reminder_alerts(&conn, r.id, false, &alerts) {
+                debug_err!("failed to update alerts of {}: {err}", r.id);
+            }
+        }
+        drop(conn);
+
+        self.next_boundary_check = Instant::now();
+    }
+
+    /// Rolls a recurring reminder forward to its first occurrence that hasn't passed yet.
+    fn roll_forward(conn: &Connection, r: &mut Reminder, now: NaiveDateTime) {
+        let Some(rule) = &r.recurrence else {
+            return;
+        };
+
+        let today = now.date();
+        let after = match r.time {
+            Some(time) if time <= now.time() => today,
+            _ => today.checked_sub_days(Days::new(1)).unwrap_or(today),
+        };
+
+        let Some((next_date, next_rule)) = rule.advance(r.date, after) else {
+            // Series ended; the last occurrence stays as a regular (past) reminder.
+            return;
+        };
+
+        r.date = next_date;
+        r.recurrence = Some(next_rule);
+        r.alerts = alerts::fresh_occurrence_alerts(now, r);
+
+        if let Some(rule) = &r.recurrence
+            && let Err(err) =
+                db_operations::roll_reminder_forward(conn, r.id, r.date, rule, &r.alerts)
+        {
+            debug_err!("failed to roll reminder {} forward: {err}", r.id);
+        }
+    }
+
+    pub(super) fn maybe_check_boundary_notifications(&mut self) {
+        let now = Instant::now();
+        if now < self.next_boundary_check {
+            return;
+        }
+
+        self.next_boundary_check = now
+            + Duration::from_secs(if self.background {
+                self.settings.background_check_interval_secs.max(1)
+            } else {
+                self.settings.check_interval_secs.max(1)
+            });
+
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+
+        let local_now = Local::now().naive_local();
+        let reminders = match db_operations::list_reminders(&db.borrow()) {
+            Ok(r) => r,
+            Err(err) => {
+                debug_err!("failed to list reminders for notifications: {err}");
+                return;
+            }
+        };
+
+        for mut r in reminders {
+            if let Some(until) = r.snoozed_until {
+                if local_now < until {
+                    // Held back; wake up when the snooze runs out.
+                    if let Ok(left) = (until - local_now).to_std() {
+                        self.next_boundary_check = self.next_boundary_check.min(now + left);
+                    }
+                    continue;
+                }
+
+                self.refire_snoozed(&db.borrow(), &r, local_now);
+                continue;
+            }
+
+            if r.recurrence.is_some() && Self::occurrence_passed(local_now, &r) {
+                Self::roll_forward(&db.borrow(), &mut r, local_now);
+            }
+
+            // Overdue: announce once when it happens, then (optionally) once per day until
+            // the reminder is completed or rescheduled. Recurring ones roll forward instead.
+            let today = local_now.date();
+            if r.recurrence.is_none()
+                && r.is_overdue(today)
+                && match r.overdue_notified_on {
+                    None => true,
+                    Some(day) => self.settings.overdue_renag && day < today,
+                }
+            {
+                self.notifications.push_back(BoundaryNotification {
+                    id: r.id,
+                    date: r.date,
+                    time: r.time,
+                    note: r.note.clone(),
+                    priority: r.priority,
+                    event: NotificationEvent::Overdue(r.days_late(today)),
+                });
+
+                if let Err(err) =
+                    db_operations::set_reminder_overdue_notified(&db.borrow(), r.id, today)
+                {
+                    debug_err!("failed to persist overdue state for {}: {err}", r.id);
+                }
+
+                // The overdue notice says it all; alerts still pending (e.g. after the app
+                // was closed for days) go out silently instead of piling up behind it.
+                for alert in r.alerts.iter_mut().filter(|a| a.fired_at.is_none()) {
+                    alert.fired_at = Some(local_now);
+                    if let Err(err) = db_operations::set_alert_fired(
+                        &db.borrow(),
+                        r.id,
+                        alert.offset_minutes,
+                        Some(local_now),
+                    ) {
+                        debug_err!("failed to persist alert state for {}: {err}", r.id);
+                    }
+                }
+            }
+
+            // Queue *each* alert reached so we don't skip 7->3->1 transitions even if the
+            // app was closed for a while, and wake up right when the next one is due
+            // instead of waiting for the regular polling interval.
+            for alert in r.alerts.iter().filter(|a| a.fired_at.is_none()) {
+                let at = r.alert_at(alert);
+                if at > local_now {
+                    if let Ok(left) = (at - local_now).to_std() {
+                        self.next_boundary_check = self.next_boundary_check.min(now + left);
+                    }
+                    continue;
+                }
+
+                self.notifications.push_back(BoundaryNotification {
+                    id: r.id,
+                    date: r.date,
+                    time: r.time,
+                    note: r.note.clone(),
+                    priority: r.priority,
+                    event: NotificationEvent::Alert {
+                        offset_minutes: alert.offset_minutes,
+                        kind: Self::alert_severity(&r, alert.offset_minutes),
+                    },
+                });
+
+                if let Err(err) = db_operations::set_alert_fired(
+                    &db.borrow(),
+                    r.id,
+                    alert.offset_minutes,
+                    Some(local_now),
+                ) {
+                    debug_err!("failed to persist alert state for {}: {err}", r.id);
+                }
+            }
+        }
+    }
+
+    /// Snooze ran out: announce the reminder's current state once and mark every alert
+    /// reached so far as sent, so the ones crossed while snoozed aren't replayed one by one.
+    fn refire_snoozed(&mut self, conn: &Connection, r: &Reminder, now: NaiveDateTime) {
+        let today = now.date();
+        let reached: Vec<i64> = r
+            .alerts
+            .iter()
+            .filter(|a| r.alert_at(a) <= now)
+            .map(|a| a.offset_minutes)
+            .collect();
+
+        let event = if r.recurrence.is_none() && r.is_overdue(today) {
+            if let Err(err) = db_operations::set_reminder_overdue_notified(conn, r.id, today) {
+                debug_err!("failed to persist overdue state for {}: {err}", r.id);
+            }
+            Some(NotificationEvent::Overdue(r.days_late(today)))
+        } else {
+            reached
+                .iter()
+                .min()
+                .map(|&offset_minutes| NotificationEvent::Alert {
+                    offset_minutes,
+                    kind: Self::alert_severity(r, offset_minutes),
+                })
+        };
+
+        if let Some(event) = event {
+            self.notifications.push_back(BoundaryNotification {
+                id: r.id,
+                date: r.date,
+                time: r.time,
+                note: r.note.clone(),
+                priority: r.priority,
+                event,
+            });
+        }
+
+        for offset in reached {
+            let fired = r
+                .alerts
+                .iter()
+                .find(|a| a.offset_minutes == offset)
+                .and_then(|a| a.fired_at)
+                .unwrap_or(now);
+            if let Err(err) = db_operations::set_alert_fired(conn, r.id, offset, Some(fired)) {
+                debug_err!("failed to persist alert state for {}: {err}", r.id);
+            }
+        }
+        if let Err(err) = db_operations::set_reminder_snoozed(conn, r.id, None) {
+            debug_err!("failed to clear snooze for {}: {err}", r.id);
+        }
+    }
+
+    /// Holds back notifications for reminder `id` until `until` (`None` cancels the snooze).
+    pub(super) fn snooze_reminder(&mut self, id: i64, until: Option<NaiveDateTime>) {
+        let Some(db) = &self.db else {
+            return;
+        };
+
+        if let Err(err) = db_operations::set_reminder_snoozed(&db.borrow(), id, until) {
+            debug_err!("failed to snooze reminder {id}: {err}");
+        }
+        self.next_boundary_check = Instant::now();
+    }
+
+    pub(super) fn dispatch_notifications(&mut self) {
+        // Quiet hours: keep the queue; it's delivered on the first frame after they end.
+        if let Some(quiet) = self.settings.quiet_hours
+            && quiet.contains(Local::now().time())
+        {
+            return;
+        }
+
+        while let Some(n) = self.notifications.pop_front() {
+            let (kind, title) = match n.event {
+                NotificationEvent::Alert {
+                    offset_minutes,
+                    kind,
+                } => (kind, i18n::notif_title(self.lang, offset_minutes)),
+                NotificationEvent::Overdue(days_late) => (
+                    TrayNotificationKind::Error,
+                    i18n::notif_overdue_title(self.lang, days_late),
+                ),
+            };
+            // Low priority only ever gets a quiet notice, critical the loudest one.
+            let kind = match n.priority {
+                Priority::Low => TrayNotificationKind::Quiet,
+                Priority::Normal | Priority::High => kind,
+                Priority::Critical => TrayNotificationKind::Error,
+            };
+
+            let when = match n.time {
+                Some(time) => format!("{} {}", n.date, time.format("%H:%M")),
+                None => n.date.to_string(),
+            };
+            let body = format!(
+                "{}\n{}: {}",
+                n.note,
+                i18n::notif_date_label(self.lang),
+                when
+            );
+            self.sinks.deliver(&Notification {
+                reminder_id: n.id,
+                title,
+                body,
+                kind,
+                priority: n.priority,
+                date: n.date,
+                time: n.time,
+                note: n.note,
+            });
+        }
+    }
+}
@@ -0,0 +1,585 @@
+use std::error::Error;
+use std::time::Instant;
+
+use chrono::{Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use eframe::egui::{self, RichText};
+use rusqlite::Connection;
+
+use super::SilliReminder;
+use crate::alerts;
+use crate::db_operations::{self, Priority, Reminder};
+use crate::i18n;
+use crate::recurrence::Recurrence;
+use crate::tray::TrayNotificationKind;
+use crate::{debug_err, debug_log};
+
+const ROW_TEXT_SIZE: f32 = 18.0;
+
+/// In-progress inline edit of a single row in the "Planned" list.
+pub(super) struct EditDraft {
+    original: Reminder,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    note: String,
+    recurrence: Option<Recurrence>,
+    priority: Priority,
+    /// Own alert lead times; `None` follows the defaults of its tags.
+    alerts: Option<Vec<i64>>,
+    tags: Vec<i64>,
+}
+
+impl EditDraft {
+    fn new(r: &Reminder) -> Self {
+        Self {
+            original: r.clone(),
+            date: r.date,
+            time: r.time,
+            note: r.note.clone(),
+            recurrence: r.recurrence.clone(),
+            priority: r.priority,
+            alerts: r.custom_alerts.then(|| {
+                let offsets: Vec<i64> = r.alerts.iter().map(|a| a.offset_minutes).collect();
+                alerts::user_offsets(&offsets, r.time.is_some())
+            }),
+            tags: r.tags.iter().map(|t| t.id).collect(),
+        }
+    }
+}
+
+enum RowAction {
+    Delete(i64),
+    Edit(Reminder),
+    Save,
+    Cancel,
+    Complete(Reminder),
+    Restore(i64),
+    Snooze(i64, Option<NaiveDateTime>),
+}
+
+impl SilliReminder {
+    pub(super) fn ui_planed(&mut self, ui: &mut egui::Ui) {
+        let accent = ui.visuals().hyperlink_color;
+        ui.label(
+            RichText::new(i18n::ui_planned(self.lang))
+                .size(28.0)
+                .strong()
+                .color(accent),
+        );
+        ui.group(|ui| {
+            ui.set_min_size(ui.available_size());
+
+            ui.vertical(|ui| {
+                let Some(db) = self.db.clone() else {
+                    ui.label(i18n::ui_no_db(self.lang));
+                    return;
+                };
+
+                let reminders = match db_operations::list_reminders(&db.borrow()) {
+                    Ok(reminders) => reminders,
+                    Err(err) => {
+                        ui.label(i18n::ui_db_read_error(self.lang));
+                        debug_err!("failed to list reminders: {err}");
+                        return;
+                    }
+                };
+
+                let completed = match db_operations::list_completed_reminders(&db.borrow()) {
+                    Ok(completed) => completed,
+                    Err(err) => {
+                        debug_err!("failed to list completed reminders: {err}");
+                        Vec::new()
+                    }
+                };
+
+                if reminders.is_empty() && completed.is_empty() {
+                    ui.label(i18n::ui_empty(self.lang));
+                    return;
+                }
+
+                self.ui_tag_filter(ui);
+                let (reminders, completed) = match self.tag_filter {
+                    Some(id) => {
+                        let tagged = |r: &Reminder| r.tags.iter().any(|t| t.id == id);
+                        (
+                            reminders.into_iter().filter(tagged).collect(),
+                            completed.into_iter().filter(tagged).collect(),
+                        )
+                    }
+                    None => (reminders, completed),
+                };
+
+                let today = Local::now().date_naive();
+                let (overdue, upcoming): (Vec<Reminder>, Vec<Reminder>) =
+                    reminders.iter().cloned().partition(|r| r.is_overdue(today));
+
+                let mut action: Option<RowAction> = None;
+
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height())
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        if !overdue.is_empty() {
+                            let danger = ui.visuals().error_fg_color;
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} ({})",
+                                    i18n::ui_overdue(self.lang),
+                                    overdue.len()
+                                ))
+                                .strong()
+                                .color(danger),
+                            );
+                            if let Some(a) = self.ui_reminder_rows(ui, &overdue) {
+                                action = Some(a);
+                            }
+
+                            if !upcoming.is_empty() {
+                                ui.label(RichText::new(i18n::ui_upcoming(self.lang)).strong());
+                            }
+                        }
+
+                        if let Some(a) = self.ui_reminder_rows(ui, &upcoming) {
+                            action = Some(a);
+                        }
+
+                        if reminders.is_empty() {
+                            ui.label(i18n::ui_empty(self.lang));
+                        }
+
+                        if !completed.is_empty()
+                            && let Some(a) = self.ui_done_section(ui, &completed)
+                        {
+                            action = Some(a);
+                        }
+                    });
+
+                if let Some(action) = action {
+                    self.apply_row_action(&db.borrow(), action);
+                }
+            });
+        });
+    }
+
+    /// "All" plus one chip per tag; clicking a chip lists only reminders with that tag.
+    fn ui_tag_filter(&mut self, ui: &mut egui::Ui) {
+        if self.tags.is_empty() {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            if ui
+                .selectable_label(self.tag_filter.is_none(), i18n::ui_filter_all(self.lang))
+                .clicked()
+            {
+                self.tag_filter = None;
+            }
+            for tag in &self.tags {
+                let on = self.tag_filter == Some(tag.id);
+                if super::tags::tag_chip(ui, tag, on).clicked() {
+                    self.tag_filter = (!on).then_some(tag.id);
+                }
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    fn ui_reminder_rows(&mut self, ui: &mut egui::Ui, rows: &[Reminder]) -> Option<RowAction> {
+        let mut action = None;
+
+        for r in rows {
+            ui.push_id(r.id, |ui| {
+                egui::Frame::NONE
+                    .fill(ui.visuals().faint_bg_color)
+                    .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+                    .corner_radius(egui::CornerRadius::same(6))
+                    .inner_margin(egui::Margin::symmetric(8, 6))
+                    .show(ui, |ui| {
+                        let row_action =
+                            if self.editing.as_ref().is_some_and(|d| d.original.id == r.id) {
+                                self.ui_edit_row(ui)
+                            } else {
+                                self.ui_view_row(ui, r)
+                            };
+                        if row_action.is_some() {
+                            action = row_action;
+                        }
+                    });
+                ui.add_space(4.0);
+            });
+        }
+
+        action
+    }
+
+    fn ui_view_row(&self, ui: &mut egui::Ui, r: &Reminder) -> Option<RowAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            let row_h = ui.spacing().interact_size.y.max(ROW_TEXT_SIZE + 10.0);
+
+            let now = Local::now().naive_local();
+            let reminder_color = match Self::urgency(now, r) {
+                // Until its first alert, the priority tints it.
+                None => match r.priority {
+                    Priority::Low => ui.visuals().weak_text_color(),
+                    Priority::Normal => ui.visuals().text_color(),
+                    Priority::High => ui.visuals().warn_fg_color,
+                    Priority::Critical => ui.visuals().error_fg_color,
+                },
+                Some(TrayNotificationKind::Quiet | TrayNotificationKind::Info) => {
+                    ui.visuals().hyperlink_color
+                }
+                Some(TrayNotificationKind::Warning) => ui.visuals().warn_fg_color,
+                Some(TrayNotificationKind::Error) => ui.visuals().error_fg_color,
+            };
+
+            let mut done = false;
+            if ui
+                .checkbox(&mut done, "")
+                .on_hover_text(i18n::ui_mark_done(self.lang))
+                .changed()
+            {
+                action = Some(RowAction::Complete(r.clone()));
+            }
+
+            let when = format_when(r);
+            let repeat = if r.recurrence.is_some() { "↻ " } else { "" };
+            let flag = match r.priority {
+                Priority::High => "! ",
+                Priority::Critical => "!! ",
+                Priority::Low | Priority::Normal => "",
+            };
+            let today = now.date();
+            let late = if r.is_overdue(today) {
+                format!("  ·  {}", i18n::days_late(self.lang, r.days_late(today)))
+            } else {
+                String::new()
+            };
+            let snoozed = match r.snoozed_until {
+                Some(until) if until > now => format!(
+                    "  ·  💤 {} {}",
+                    i18n::ui_snoozed_until(self.lang),
+                    format_snoozed_until(until, today)
+                ),
+                _ => String::new(),
+            };
+            let row_text = RichText::new(format!(
+                "{flag}{repeat}{}  -  {}{late}{snoozed}",
+                when, r.note
+            ))
+            .size(ROW_TEXT_SIZE)
+            .color(reminder_color);
+
+            let mut hover = match &r.recurrence {
+                Some(rule) => format!(
+                    "{}\n{}",
+                    i18n::recurrence_summary(self.lang, rule),
+                    i18n::ui_click_to_edit(self.lang)
+                ),
+                None => i18n::ui_click_to_edit(self.lang).to_owned(),
+            };
+            if r.priority != Priority::Normal {
+                hover = format!(
+                    "{} {}\n{hover}",
+                    i18n::ui_priority(self.lang),
+                    i18n::priority_name(self.lang, r.priority)
+                );
+            }
+            if ui
+                .add(egui::Label::new(row_text).sense(egui::Sense::click()))
+                .on_hover_text(hover)
+                .clicked()
+            {
+                action = Some(RowAction::Edit(r.clone()));
+            }
+
+            for tag in &r.tags {
+                super::tags::tag_chip(ui, tag, true);
+            }
+
+            let remaining = ui.available_width();
+            ui.allocate_ui_with_layout(
+                egui::vec2(remaining, 0.0),
+                egui::Layout::right_to_left(egui::Align::Center),
+                |ui| {
+                    let danger = ui.visuals().error_fg_color;
+                    let x = egui::Button::new(RichText::new("X").size(22.0).color(danger));
+                    if ui.add_sized(egui::vec2(36.0, row_h), x).clicked() {
+                        action = Some(RowAction::Delete(r.id));
+                    }
+
+                    ui.menu_button(RichText::new("💤").size(18.0), |ui| {
+                        for hours in [1, 2] {
+                            if ui.button(i18n::ui_snooze_hours(self.lang, hours)).clicked() {
+                                let until = now + Duration::hours(i64::from(hours));
+                                action = Some(RowAction::Snooze(r.id, Some(until)));
+                            }
+                        }
+                        if ui.button(i18n::ui_snooze_tomorrow(self.lang)).clicked() {
+                            let until = today
+                                .checked_add_days(Days::new(1))
+                                .and_then(|d| d.and_hms_opt(9, 0, 0));
+                            action = Some(RowAction::Snooze(r.id, until));
+                        }
+                        if r.snoozed_until.is_some()
+                            && ui.button(i18n::ui_snooze_cancel(self.lang)).clicked()
+                        {
+                            action = Some(RowAction::Snooze(r.id, None));
+                        }
+                    })
+                    .response
+                    .on_hover_text(i18n::ui_snooze(self.lang));
+                },
+            );
+        });
+
+        action
+    }
+
+    fn ui_done_section(&self, ui: &mut egui::Ui, completed: &[Reminder]) -> Option<RowAction> {
+        let mut action = None;
+
+        ui.add_space(4.0);
+        egui::CollapsingHeader::new(format!(
+            "{} ({})",
+            i18n::ui_done(self.lang),
+            completed.len()
+        ))
+        .id_salt("done_section")
+        .show(ui, |ui| {
+            for r in completed {
+                ui.push_id(("done", r.id), |ui| {
+                    ui.horizontal(|ui| {
+                        let text =
+                            RichText::new(format!("✔ {}  -  {}", format_when(r), r.note)).weak();
+                        let label = ui.label(text);
+                        if let Some(at) = r.completed_at {
+                            label.on_hover_text(format!(
+                                "{}: {}",
+                                i18n::ui_done_at(self.lang),
+                                at.format("%Y-%m-%d %H:%M")
+                            ));
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let danger = ui.visuals().error_fg_color;
+                            if ui.button(RichText::new("X").color(danger)).clicked() {
+                                action = Some(RowAction::Delete(r.id));
+                            }
+                            if ui
+                                .button("↺")
+                                .on_hover_text(i18n::ui_restore(self.lang))
+                                .clicked()
+                            {
+                                action = Some(RowAction::Restore(r.id));
+                            }
+                        });
+                    });
+                });
+            }
+        });
+
+        action
+    }
+
+    fn ui_edit_row(&mut self, ui: &mut egui::Ui) -> Option<RowAction> {
+        let lang = self.lang;
+        let defaults = self.default_offsets(&self.editing.as_ref()?.tags);
+        let all_tags = &self.tags;
+        let draft = self.editing.as_mut()?;
+        let mut action = None;
+
+        ui.vertical(|ui| {
+            let row_h = ui.spacing().interact_size.y;
+
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    egui::vec2(120.0, row_h),
+                    crate::widgets::DatePickerPlButton::new(&mut draft.date)
+                        .id_salt("edit_date")
+                        .format("%Y-%m-%d")
+                        .language(lang),
+                );
+                ui.add(
+                    crate::widgets::TimeInput::new(&mut draft.time)
+                        .id_salt("edit_time")
+                        .language(lang),
+                );
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_sized(egui::vec2(30.0, row_h), egui::Button::new("✖"))
+                        .on_hover_text(i18n::ui_cancel(lang))
+                        .clicked()
+                    {
+                        action = Some(RowAction::Cancel);
+                    }
+                    if ui
+                        .add_sized(egui::vec2(30.0, row_h), egui::Button::new("✔"))
+                        .on_hover_text(i18n::ui_save(lang))
+                        .clicked()
+                    {
+                        action = Some(RowAction::Save);
+                    }
+
+                    let note_response = ui.add_sized(
+                        egui::vec2(ui.available_width(), row_h),
+                        egui::TextEdit::singleline(&mut draft.note)
+                            .id_salt("edit_note")
+                            .hint_text(i18n::ui_note_hint(lang)),
+                    );
+                    if note_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        action = Some(RowAction::Save);
+                    }
+                });
+            });
+
+            ui.add(
+                crate::widgets::RecurrenceInput::new(&mut draft.recurrence, draft.date)
+                    .id_salt("edit_recurrence")
+                    .language(lang),
+            );
+
+            super::add_form::ui_priority(ui, lang, "edit_priority", &mut draft.priority);
+
+            if !all_tags.is_empty() {
+                super::tags::ui_tag_picker(ui, all_tags, &mut draft.tags);
+            }
+
+            super::add_form::ui_alerts_override(
+                ui,
+                lang,
+                "edit_alerts",
+                &mut draft.alerts,
+                &defaults,
+            );
+        });
+
+        if action.is_none() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            action = Some(RowAction::Cancel);
+        }
+
+        action
+    }
+
+    fn apply_row_action(&mut self, conn: &Connection, action: RowAction) {
+        match action {
+            RowAction::Delete(id) => {
+                if let Err(err) = db_operations::delete_reminder(conn, id) {
+                    debug_err!("failed to delete reminder {id}: {err}");
+                }
+                if self.editing.as_ref().is_some_and(|d| d.original.id == id) {
+                    self.editing = None;
+                }
+            }
+            RowAction::Edit(r) => {
+                self.editing = Some(EditDraft::new(&r));
+            }
+            RowAction::Cancel => {
+                self.editing = None;
+            }
+            RowAction::Save => self.save_edit(conn),
+            RowAction::Complete(r) => {
+                let now = Local::now().naive_local();
+                if let Err(err) = db_operations::complete_reminder(conn, &r, now) {
+                    debug_err!("failed to complete reminder {}: {err}", r.id);
+                }
+            }
+            RowAction::Restore(id) => {
+                if let Err(err) = self.restore_reminder(conn, id) {
+                    debug_err!("failed to restore reminder {id}: {err}");
+                }
+                self.next_boundary_check = Instant::now();
+            }
+            RowAction::Snooze(id, until) => self.snooze_reminder(id, until),
+        }
+    }
+
+    fn save_edit(&mut self, conn: &Connection) {
+        let Some(draft) = self.editing.take() else {
+            return;
+        };
+
+        let note = draft.note.trim();
+        if note.is_empty() {
+            debug_err!("note is empty; edit not saved");
+            self.editing = Some(draft);
+            return;
+        }
+
+        let original = &draft.original;
+        let mut edited = original.clone();
+        edited.date = draft.date;
+        edited.time = draft.time;
+        edited.note = note.to_owned();
+        edited.recurrence = draft.recurrence.clone();
+        edited.priority = draft.priority;
+        edited.custom_alerts = draft.alerts.is_some();
+        edited.tags = self
+            .tags
+            .iter()
+            .filter(|t| draft.tags.contains(&t.id))
+            .cloned()
+            .collect();
+
+        // Moving a reminder later re-arms the alerts it hasn't reached anymore,
+        // so e.g. "≤ 1 day" fires again for the new date.
+        let now = Local::now().naive_local();
+        let offsets =
+            self.alert_offsets(draft.alerts.as_deref(), &draft.tags, edited.time.is_some());
+        edited.alerts = alerts::carry_alerts(now, &edited, &offsets, &original.alerts);
+
+        if edited.due_at() > original.due_at() {
+            edited.overdue_notified_on = None;
+            edited.snoozed_until = None;
+        }
+
+        match db_operations::update_reminder(conn, &edited) {
+            Ok(()) => {
+                debug_log!("Zmieniono #{}: {}, {}", edited.id, edited.date, edited.note);
+                // Re-evaluate notifications for the new date right away.
+                self.next_boundary_check = Instant::now();
+            }
+            Err(err) => {
+                debug_err!("failed to update reminder {}: {err}", edited.id);
+                self.editing = Some(draft);
+            }
+        }
+    }
+
+    /// Brings a completed reminder back. One without its own alerts gets the current default
+    /// lead times, so an occurrence archived by an older version (without alerts) still notifies.
+    fn restore_reminder(&self, conn: &Connection, id: i64) -> Result<(), Box<dyn Error>> {
+        db_operations::set_reminder_completed(conn, id, None)?;
+        let Some(r) = db_operations::get_reminder(conn, id)? else {
+            return Ok(());
+        };
+        if r.custom_alerts {
+            return Ok(());
+        }
+
+        let tag_ids: Vec<i64> = r.tags.iter().map(|t| t.id).collect();
+        let offsets = self.alert_offsets(None, &tag_ids, r.time.is_some());
+        let alerts = alerts::carry_alerts(Local::now().naive_local(), &r, &offsets, &r.alerts);
+        if alerts != r.alerts {
+            db_operations::set_reminder_alerts(conn, id, false, &alerts)?;
+        }
+        Ok(())
+    }
+}
+
+fn format_when(r: &Reminder) -> String {
+    match r.time {
+        Some(time) => format!("{} {}", r.date, time.format("%H:%M")),
+        None => r.date.to_string(),
+    }
+}
+
+/// Snooze end as shown in a row: just the time when it's today.
+fn format_snoozed_until(until: NaiveDateTime, today: NaiveDate) -> String {
+    if until.date() == today {
+        until.format("%H:%M").to_string()
+    } else {
+        until.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
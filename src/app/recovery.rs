@@ -0,0 +1,150 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use eframe::egui::{self, RichText};
+use rusqlite::Connection;
+
+use super::SilliReminder;
+use super::data::DataStatus;
+use crate::db_operations::{self, CorruptDatabaseError, NewerSchemaError};
+use crate::{debug_err, debug_log, i18n};
+
+/// Why the database couldn't be opened.
+pub(super) enum DbProblem {
+    /// A newer version of the app upgraded it.
+    TooNew,
+    /// The file is damaged; what `integrity_check` found.
+    Damaged(Vec<String>),
+    /// Anything else (locked, no permission, ...).
+    Unavailable(String),
+}
+
+impl fmt::Display for DbProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooNew => write!(f, "the database was upgraded by a newer version"),
+            Self::Damaged(problems) => match problems.first() {
+                Some(first) => write!(f, "the database is damaged ({first})"),
+                None => write!(f, "the database is damaged"),
+            },
+            Self::Unavailable(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// Opens the database, logging why when it can't be.
+pub(super) fn open_db() -> Result<Rc<RefCell<Connection>>, DbProblem> {
+    db_operations::get_db().map_err(|err| {
+        debug_err!("failed to open database: {err}");
+        problem(err.as_ref())
+    })
+}
+
+fn problem(err: &(dyn Error + 'static)) -> DbProblem {
+    if err.is::<NewerSchemaError>() {
+        DbProblem::TooNew
+    } else if let Some(corrupt) = err.downcast_ref::<CorruptDatabaseError>() {
+        DbProblem::Damaged(corrupt.problems.clone())
+    } else {
+        DbProblem::Unavailable(err.to_string())
+    }
+}
+
+impl SilliReminder {
+    /// Shown instead of the rest of the window while the database can't be opened: what
+    /// happened, and the ways out (recover, restore a backup, try again).
+    pub(super) fn ui_db_problem(&mut self, ui: &mut egui::Ui) {
+        let Some(problem) = &self.db_problem else {
+            return;
+        };
+
+        ui.label(
+            RichText::new(i18n::ui_db_problem(self.lang))
+                .size(25.0)
+                .strong()
+                .color(ui.visuals().error_fg_color),
+        );
+        ui.add_space(6.0);
+
+        let damaged = matches!(problem, DbProblem::Damaged(_));
+        match problem {
+            DbProblem::TooNew => {
+                ui.label(i18n::ui_db_too_new(self.lang));
+            }
+            DbProblem::Damaged(problems) => {
+                ui.label(i18n::ui_db_damaged(self.lang));
+                egui::CollapsingHeader::new(i18n::ui_db_details(self.lang))
+                    .id_salt("db_problems")
+                    .show(ui, |ui| {
+                        for line in problems {
+                            ui.label(RichText::new(line).monospace().weak());
+                        }
+                    });
+            }
+            DbProblem::Unavailable(error) => {
+                ui.label(i18n::ui_db_unavailable(self.lang, error));
+            }
+        }
+        ui.add_space(6.0);
+
+        ui.horizontal_wrapped(|ui| {
+            if damaged && ui.button(i18n::ui_recover(self.lang)).clicked() {
+                self.recover();
+            }
+            // Only a damaged file is replaced; see `db_operations::restore_backup_file`.
+            if damaged && ui.button(i18n::ui_restore_backup(self.lang)).clicked() {
+                self.toggle_backups();
+            }
+            if ui.button(i18n::ui_retry(self.lang)).clicked() {
+                self.data_status = None;
+                self.reopen_db();
+            }
+        });
+
+        self.ui_backups(ui);
+        self.ui_data_status(ui);
+    }
+
+    /// Tries to open the database again, e.g. after the damaged file was replaced.
+    pub(super) fn reopen_db(&mut self) {
+        match open_db() {
+            Ok(db) => {
+                self.db = Some(db);
+                self.db_problem = None;
+                self.reload_tags();
+                // Catch up on anything that came due while the database was unavailable.
+                self.next_boundary_check = std::time::Instant::now();
+            }
+            Err(problem) => self.db_problem = Some(problem),
+        }
+    }
+
+    fn recover(&mut self) {
+        self.backups = None;
+        match db_operations::recover_db() {
+            Ok(report) => {
+                debug_log!("database recovered: {report:?}");
+                let mut message = i18n::ui_recovered(
+                    self.lang,
+                    report.reminders,
+                    &report.quarantined.display().to_string(),
+                );
+                if !report.complete {
+                    message.push(' ');
+                    message.push_str(i18n::ui_recovered_partly(self.lang));
+                }
+                self.data_status = Some(DataStatus::Done(message));
+                self.reopen_db();
+            }
+            Err(err) => {
+                debug_err!("recovery failed: {err}");
+                self.data_status = Some(DataStatus::Failed(i18n::ui_recover_failed(
+                    self.lang,
+                    &err.to_string(),
+                )));
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::error::Error;
+
+use eframe::egui::{self, Color32, RichText};
+
+use super::SilliReminder;
+use crate::alerts;
+use crate::db_operations::{self, TAG_COLORS, Tag};
+use crate::i18n;
+use crate::{debug_err, debug_log};
+
+impl SilliReminder {
+    /// Re-reads the tags, e.g. after the database was replaced.
+    pub(super) fn reload_tags(&mut self) {
+        let Some(db) = &self.db else {
+            self.tags.clear();
+            return;
+        };
+        match db_operations::list_tags(&db.borrow()) {
+            Ok(tags) => self.tags = tags,
+            Err(err) => debug_err!("failed to list tags: {err}"),
+        }
+
+        let known = |id: &i64| self.tags.iter().any(|t| t.id == *id);
+        self.selected_tags.retain(known);
+        if self.tag_filter.is_some_and(|id| !known(&id)) {
+            self.tag_filter = None;
+        }
+    }
+
+    /// Default lead times for a reminder with `tag_ids`: those of its tags that have their
+    /// own (all of them together), else the global defaults.
+    pub(super) fn default_offsets(&self, tag_ids: &[i64]) -> Vec<i64> {
+        alerts::default_offsets(
+            self.tags.iter().filter(|t| tag_ids.contains(&t.id)),
+            &self.settings.alert_offsets,
+        )
+    }
+
+    /// The "Tags" part of Settings: create, rename, recolor and delete tags, and give them
+    /// lead times of their own.
+    pub(super) fn ui_tags(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(format!(
+            "{} ({})",
+            i18n::ui_tags(self.lang),
+            self.tags.len()
+        ))
+        .id_salt("tags_settings")
+        .show(ui, |ui| {
+            let mut changed = None;
+            let mut deleted = None;
+            let defaults = self.settings.alert_offsets.clone();
+
+            for (i, tag) in self.tags.iter_mut().enumerate() {
+                ui.push_id(tag.id, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.color_edit_button_srgb(&mut tag.color).changed() {
+                            changed = Some(i);
+                        }
+                        let name =
+                            ui.add(egui::TextEdit::singleline(&mut tag.name).desired_width(120.0));
+                        if name.lost_focus() {
+                            changed = Some(i);
+                        }
+                        if super::add_form::ui_alerts_override(
+                            ui,
+                            self.lang,
+                            "tag_alerts",
+                            &mut tag.alert_offsets,
+                            &defaults,
+                        ) {
+                            changed = Some(i);
+                        }
+                        let danger = ui.visuals().error_fg_color;
+                        if ui
+                            .button(RichText::new("X").color(danger))
+                            .on_hover_text(i18n::ui_delete_tag(self.lang))
+                            .clicked()
+                        {
+                            deleted = Some(tag.id);
+                        }
+                    });
+                });
+            }
+
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.new_tag_name)
+                        .id_salt("new_tag")
+                        .desired_width(160.0)
+                        .hint_text(i18n::ui_new_tag_hint(self.lang)),
+                );
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button(i18n::ui_add_button(self.lang)).clicked() || entered {
+                    self.add_tag();
+                }
+            });
+
+            if let Some(error) = &self.tag_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            if let Some(i) = changed {
+                self.save_tag(i);
+            }
+            if let Some(id) = deleted {
+                self.delete_tag(id);
+            }
+        });
+    }
+
+    fn add_tag(&mut self) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let name = self.new_tag_name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let color = TAG_COLORS[self.tags.len() % TAG_COLORS.len()];
+        let result = db_operations::insert_tag(&db.borrow(), name, color, None);
+        self.tag_error = None;
+        match result {
+            Ok(id) => {
+                debug_log!("tag #{id} added: {name}");
+                self.new_tag_name.clear();
+            }
+            Err(err) => {
+                debug_err!("failed to add tag {name:?}: {err}");
+                self.tag_error = Some(save_error(self.lang, err.as_ref()));
+            }
+        }
+        self.reload_tags();
+    }
+
+    /// Stores the edited tag `self.tags[i]`. A name that is empty or already taken is put back.
+    fn save_tag(&mut self, i: usize) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let tag = &mut self.tags[i];
+        tag.name = tag.name.trim().to_owned();
+
+        let result = if tag.name.is_empty() {
+            Err("tag name is empty".into())
+        } else {
+            db_operations::update_tag(&db.borrow(), tag)
+        };
+        self.tag_error = None;
+        match result {
+            Ok(()) => debug_log!("tag #{} -> {tag:?}", tag.id),
+            Err(err) => {
+                debug_err!("failed to update tag #{}: {err}", tag.id);
+                if !tag.name.is_empty() {
+                    self.tag_error = Some(save_error(self.lang, err.as_ref()));
+                }
+            }
+        }
+
+        self.reload_tags();
+        // Its reminders may follow different lead times now.
+        self.apply_default_alerts();
+    }
+
+    fn delete_tag(&mut self, id: i64) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        if let Err(err) = db_operations::delete_tag(&db.borrow(), id) {
+            debug_err!("failed to delete tag #{id}: {err}");
+        }
+        self.reload_tags();
+        self.apply_default_alerts();
+    }
+}
+
+/// What to tell the user when a tag couldn't be saved: that its name is taken (the only
+/// constraint on tags), or else what went wrong.
+fn save_error(lang: i18n::Language, err: &(dyn Error + 'static)) -> String {
+    let taken = matches!(
+        err.downcast_ref::<rusqlite::Error>(),
+        Some(rusqlite::Error::SqliteFailure(e, _))
+            if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+    );
+    if taken {
+        i18n::ui_tag_exists(lang).to_owned()
+    } else {
+        i18n::ui_tag_save_failed(lang, &err.to_string())
+    }
+}
+
+/// Tags to pick for a reminder, as chips that toggle. Returns whether the selection changed.
+pub(super) fn ui_tag_picker(ui: &mut egui::Ui, tags: &[Tag], selected: &mut Vec<i64>) -> bool {
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        for tag in tags {
+            let on = selected.contains(&tag.id);
+            if tag_chip(ui, tag, on).clicked() {
+                if on {
+                    selected.retain(|id| *id != tag.id);
+                } else {
+                    selected.push(tag.id);
+                }
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+/// A tag as a small rounded label: filled with its color when `filled`, outlined otherwise.
+pub(super) fn tag_chip(ui: &mut egui::Ui, tag: &Tag, filled: bool) -> egui::Response {
+    let [r, g, b] = tag.color;
+    let color = Color32::from_rgb(r, g, b);
+    let (fill, text) = if filled {
+        (color, readable_on(color))
+    } else {
+        (Color32::TRANSPARENT, color)
+    };
+
+    let mut hover = tag.name.clone();
+    if let Some(offsets) = &tag.alert_offsets {
+        hover = format!("{hover}: {}", alerts::format_offsets(offsets));
+    }
+
+    egui::Frame::NONE
+        .fill(fill)
+        .stroke(egui::Stroke::new(1.0, color))
+        .corner_radius(egui::CornerRadius::same(8))
+        .inner_margin(egui::Margin::symmetric(6, 1))
+        .show(ui, |ui| {
+            ui.label(RichText::new(&tag.name).small().color(text));
+        })
+        .response
+        .interact(egui::Sense::click())
+        .on_hover_text(hover)
+}
+
+/// Black or white, whichever reads better on `background`.
+fn readable_on(background: Color32) -> Color32 {
+    let [r, g, b, _] = background.to_array();
+    let luma = 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b);
+    if luma > 150.0 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
@@ -1,15 +1,64 @@
 use std::error::Error;
+use std::path::{Path, PathBuf};
 
-pub fn set_enabled(enabled: bool) -> Result<(), Box<dyn Error>> {
+use crate::settings::AutostartMethod;
+
+/// What the system has registered, compared with this executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// No autostart entry.
+    Absent,
+    /// An entry that launches this executable.
+    Present,
+    /// An entry that launches another path, e.g. after the EXE was moved or reinstalled
+    /// elsewhere.
+    PointsElsewhere(PathBuf),
+}
+
+impl Status {
+    pub fn is_registered(&self) -> bool {
+        !matches!(self, Status::Absent)
+    }
+}
+
+/// Registers or removes the app's autostart entry. `method` only matters on Linux; Windows
+/// always uses the `Run` registry key.
+pub fn set_enabled(enabled: bool, method: AutostartMethod) -> Result<(), Box<dyn Error>> {
     if enabled {
-        add_to_autostart()
+        add_to_autostart(method)
     } else {
         remove_from_autostart()
     }
 }
 
+/// Reads back the registered autostart entry, if any, and checks where it points.
+pub fn status() -> Result<Status, Box<dyn Error>> {
+    Ok(match registered_target()? {
+        None => Status::Absent,
+        Some(target) if is_current_exe(&target) => Status::Present,
+        Some(target) => Status::PointsElsewhere(target),
+    })
+}
+
+fn is_current_exe(target: &Path) -> bool {
+    let Ok(exe) = std::env::current_exe() else {
+        // Can't tell; don't report a mismatch we can't back up.
+        return true;
+    };
+
+    let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let (target, exe) = (canonical(target), canonical(&exe));
+    if cfg!(target_os = "windows") {
+        target
+            .to_string_lossy()
+            .eq_ignore_ascii_case(&exe.to_string_lossy())
+    } else {
+        target == exe
+    }
+}
+
 #[cfg(target_os = "windows")]
-fn add_to_autostart() -> Result<(), Box<dyn Error>> {
+fn add_to_autostart(_method: AutostartMethod) -> Result<(), Box<dyn Error>> {
     windows::add_to_autostart()
 }
 
@@ -18,16 +67,45 @@ fn remove_from_autostart() -> Result<(), Box<dyn Error>> {
     windows::remove_from_autostart()
 }
 
-#[cfg(not(target_os = "windows"))]
-fn add_to_autostart() -> Result<(), Box<dyn Error>> {
-    Ok(())
+#[cfg(target_os = "windows")]
+fn registered_target() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    windows::registered_target()
+}
+
+#[cfg(target_os = "linux")]
+fn add_to_autostart(method: AutostartMethod) -> Result<(), Box<dyn Error>> {
+    linux::add_to_autostart(method)
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+fn remove_from_autostart() -> Result<(), Box<dyn Error>> {
+    linux::remove_from_autostart()
+}
+
+#[cfg(target_os = "linux")]
+fn registered_target() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    linux::registered_target()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn add_to_autostart(_method: AutostartMethod) -> Result<(), Box<dyn Error>> {
+    Err("autostart is not supported on this platform".into())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 fn remove_from_autostart() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn registered_target() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    Ok(None)
+}
+
 #[cfg(target_os = "windows")]
 #[path = "autostart/windows.rs"]
 mod windows;
+
+#[cfg(target_os = "linux")]
+#[path = "autostart/linux.rs"]
+mod linux;
@@ -0,0 +1,347 @@
+//! Linux autostart, either as an XDG autostart entry (started by the desktop session) or as
+//! a systemd user service bound to `graphical-session.target`.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::settings::AutostartMethod;
+
+const DESKTOP_FILE: &str = "SilliReminder.desktop";
+const SERVICE: &str = "silli-reminder.service";
+const SERVICE_TARGET: &str = "graphical-session.target";
+
+/// `$XDG_CONFIG_HOME`, or `~/.config`.
+fn config_dir() -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+    Ok(PathBuf::from(home).join(".config"))
+}
+
+fn desktop_file_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config_dir()?.join("autostart").join(DESKTOP_FILE))
+}
+
+fn service_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config_dir()?.join("systemd").join("user").join(SERVICE))
+}
+
+/// The symlink `systemctl --user enable` creates.
+fn service_wants_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config_dir()?
+        .join("systemd")
+        .join("user")
+        .join(format!("{SERVICE_TARGET}.wants"))
+        .join(SERVICE))
+}
+
+/// Enable autostart for the current user with `method`, removing the other kind of entry
+/// so the app never starts twice.
+///
+/// Both run `"<path-to-exe>" --autostart`, so the app starts hidden in the tray.
+pub(super) fn add_to_autostart(method: AutostartMethod) -> Result<(), Box<dyn Error>> {
+    let exe = std::env::current_exe()?;
+
+    match method {
+        AutostartMethod::Xdg => {
+            remove_service()?;
+            let exec = format!("{} --autostart", quote_desktop_exec(&exe));
+            let contents = format!(
+                "[Desktop Entry]\n\
+                 Type=Application\n\
+                 Name=SilliReminder\n\
+                 Exec={exec}\n\
+                 Icon=appointment-soon\n\
+                 Terminal=false\n\
+                 X-GNOME-Autostart-enabled=true\n"
+            );
+            write(&desktop_file_path()?, &contents)
+        }
+        AutostartMethod::Systemd => {
+            remove_file(&desktop_file_path()?)?;
+            let exec = format!("{} --autostart", quote_systemd_exec(&exe));
+            let contents = format!(
+                "[Unit]\n\
+                 Description=SilliReminder\n\
+                 PartOf={SERVICE_TARGET}\n\
+                 After={SERVICE_TARGET}\n\
+                 \n\
+                 [Service]\n\
+                 ExecStart={exec}\n\
+                 Restart=on-failure\n\
+                 \n\
+                 [Install]\n\
+                 WantedBy={SERVICE_TARGET}\n"
+            );
+            write(&service_path()?, &contents)?;
+            systemctl(&["daemon-reload"])?;
+            systemctl(&["enable", SERVICE])
+        }
+    }
+}
+
+/// Disable autostart for the current user, whichever way it was set up.
+///
+/// Removing entries that don't exist is treated as success (idempotent operation).
+pub(super) fn remove_from_autostart() -> Result<(), Box<dyn Error>> {
+    remove_file(&desktop_file_path()?)?;
+    remove_service()
+}
+
+/// The executable the autostart entry launches, or `None` when there is no entry.
+///
+/// A desktop entry the session skips (`Hidden=true`, or switched off in GNOME's startup
+/// applications) counts as no entry.
+pub(super) fn registered_target() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let desktop_file = desktop_file_path()?;
+    if desktop_file.exists() {
+        let contents = std::fs::read_to_string(desktop_file)?;
+        if !desktop_entry_disabled(&contents) {
+            return Ok(exec_target(&contents, "Exec=", false));
+        }
+    }
+
+    if service_wants_path()?.exists() {
+        let unit = std::fs::read_to_string(service_path()?)?;
+        return Ok(exec_target(&unit, "ExecStart=", true));
+    }
+
+    Ok(None)
+}
+
+fn desktop_entry_disabled(contents: &str) -> bool {
+    contents.lines().any(|l| {
+        let Some((key, value)) = l.split_once('=') else {
+            return false;
+        };
+        matches!(
+            (key.trim(), value.trim()),
+            ("Hidden", "true") | ("X-GNOME-Autostart-enabled", "false")
+        )
+    })
+}
+
+/// The program of the first `key` line, undoing [`quote_desktop_exec`] /
+/// [`quote_systemd_exec`]. An entry without one still counts as registered, pointing nowhere.
+fn exec_target(contents: &str, key: &str, systemd: bool) -> Option<PathBuf> {
+    let Some(exec) = contents.lines().find_map(|l| l.trim().strip_prefix(key)) else {
+        return Some(PathBuf::new());
+    };
+
+    let exec = if systemd {
+        exec.trim().to_owned()
+    } else {
+        unescape_desktop_string(exec.trim())
+    };
+    let mut program = String::new();
+    match exec.strip_prefix('"') {
+        Some(rest) => {
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => program.extend(chars.next()),
+                    '"' => break,
+                    _ => program.push(c),
+                }
+            }
+        }
+        None => program.push_str(exec.split_whitespace().next().unwrap_or_default()),
+    }
+
+    let mut program = program.replace("%%", "%");
+    if systemd {
+        program = program.replace("$$", "$");
+    }
+    Some(PathBuf::from(program))
+}
+
+fn remove_service() -> Result<(), Box<dyn Error>> {
+    let path = service_path()?;
+    if !path.exists() {
+        return Ok(());
+    }
+
+    if service_wants_path()?.exists() {
+        systemctl(&["disable", SERVICE])?;
+    }
+    remove_file(&path)?;
+    systemctl(&["daemon-reload"])
+}
+
+fn systemctl(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()?;
+    if !status.success() {
+        return Err(format!("systemctl --user {} failed: {status}", args.join(" ")).into());
+    }
+    Ok(())
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+fn remove_file(path: &Path) -> Result<(), Box<dyn Error>> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+/// Quotes a path for a desktop entry's `Exec=` line (`%` starts a field code there). The
+/// whole value is a string too, whose own escaping is undone before the quoting, so each
+/// backslash is written twice on top (a literal one becomes four).
+fn quote_desktop_exec(path: &Path) -> String {
+    let mut out = String::from("\"");
+    for c in path.display().to_string().chars() {
+        match c {
+            '"' | '`' | '$' => {
+                out.push_str("\\\\");
+                out.push(c);
+            }
+            '\\' => out.push_str("\\\\\\\\"),
+            '%' => out.push_str("%%"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Undoes the escaping of a desktop entry string value (`\\`, `\s`, `\n`, `\t`, `\r`).
+fn unescape_desktop_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Quotes a path for a unit's `ExecStart=` line (`$` expands variables, `%` specifiers).
+fn quote_systemd_exec(path: &Path) -> String {
+    let mut out = String::from("\"");
+    for c in path.display().to_string().chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '$' => out.push_str("$$"),
+            '%' => out.push_str("%%"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AWKWARD: &[&str] = &[
+        "/usr/bin/silli-reminder",
+        "/opt/Silli Reminder/bin/silli-reminder",
+        "/home/u/50% \"off\"/silli",
+        "/home/u/$HOME/a`b`/c\\d/%%/$$/silli",
+    ];
+
+    #[test]
+    fn quotes_desktop_exec() {
+        assert_eq!(
+            quote_desktop_exec(Path::new("/opt/Silli Reminder/silli")),
+            r#""/opt/Silli Reminder/silli""#
+        );
+        assert_eq!(
+            quote_desktop_exec(Path::new(r#"/a"b$c%d\e`f"#)),
+            r#""/a\\"b\\$c%%d\\\\e\\`f""#
+        );
+    }
+
+    #[test]
+    fn quotes_systemd_exec() {
+        assert_eq!(
+            quote_systemd_exec(Path::new("/opt/Silli Reminder/silli")),
+            r#""/opt/Silli Reminder/silli""#
+        );
+        assert_eq!(
+            quote_systemd_exec(Path::new(r#"/a"b$c%d\e`f"#)),
+            r#""/a\"b$$c%%d\\e`f""#
+        );
+    }
+
+    #[test]
+    fn reads_back_quoted_programs() {
+        for path in AWKWARD.iter().map(Path::new) {
+            let desktop = format!(
+                "[Desktop Entry]\nType=Application\nExec={} --autostart\n",
+                quote_desktop_exec(path)
+            );
+            assert_eq!(exec_target(&desktop, "Exec=", false).as_deref(), Some(path));
+
+            let unit = format!(
+                "[Service]\nExecStart={} --autostart\n",
+                quote_systemd_exec(path)
+            );
+            assert_eq!(
+                exec_target(&unit, "ExecStart=", true).as_deref(),
+                Some(path)
+            );
+        }
+    }
+
+    #[test]
+    fn reads_hand_written_exec_lines() {
+        assert_eq!(
+            exec_target("Exec=/usr/bin/silli --autostart", "Exec=", false),
+            Some(PathBuf::from("/usr/bin/silli"))
+        );
+        assert_eq!(
+            exec_target(r#"Exec="/opt/Silli\sReminder/silli" %u"#, "Exec=", false),
+            Some(PathBuf::from("/opt/Silli Reminder/silli"))
+        );
+        assert_eq!(
+            exec_target("[Desktop Entry]\nName=Silli\n", "Exec=", false),
+            Some(PathBuf::new())
+        );
+    }
+
+    #[test]
+    fn detects_disabled_entries() {
+        for contents in [
+            "[Desktop Entry]\nHidden=true\n",
+            "[Desktop Entry]\nX-GNOME-Autostart-enabled=false\n",
+            "[Desktop Entry]\n  Hidden = true  \n",
+        ] {
+            assert!(desktop_entry_disabled(contents), "{contents:?}");
+        }
+        for contents in [
+            "[Desktop Entry]\nHidden=false\nX-GNOME-Autostart-enabled=true\n",
+            "[Desktop Entry]\nNoDisplay=true\n",
+            "[Desktop Entry]\nName=Hidden=true\n",
+        ] {
+            assert!(!desktop_entry_disabled(contents), "{contents:?}");
+        }
+    }
+}
@@ -51,3 +51,29 @@ pub(super) fn remove_from_autostart() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// The executable the `Run` value launches, or `None` when there is no value.
+pub(super) fn registered_target() -> Result<Option<std::path::PathBuf>, Box<dyn Error>> {
+    use winreg::RegKey;
+    use winreg::enums::*;
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let run_key = hkcu.open_subkey_with_flags(
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Run",
+        KEY_READ,
+    )?;
+
+    let command: String = match run_key.get_value("SilliReminder") {
+        Ok(command) => command,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    // `"C:\path\SilliReminder.exe" --autostart`, or unquoted if edited by hand.
+    let command = command.trim();
+    let path = match command.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or(rest),
+        None => command.split(" --").next().unwrap_or(command),
+    };
+    Ok(Some(path.trim().into()))
+}
@@ -0,0 +1,463 @@
+//! Command-line interface, for scripting reminders from shells and build pipelines.
+//!
+//! Without a subcommand the app starts as usual (`--background` / `--autostart` start it
+//! hidden in the tray). With one, it works on the database directly through
+//! [`db_operations`], prints the result and exits without opening a window:
+//!
+//! ```text
+//! SilliReminder add 2026-11-03 "renew cert" --time 09:00 --alerts "14d, 1d" --priority high
+//! SilliReminder list --json
+//! SilliReminder done 12
+//! SilliReminder snooze 12 1d
+//! SilliReminder delete 12
+//! SilliReminder export reminders.ics --all
+//! SilliReminder import calendar.ics
+//! SilliReminder import seed.csv --dry-run
+//! ```
+//!
+//! Exit codes: [`EXIT_OK`], [`EXIT_FAILED`], [`EXIT_NOT_FOUND`], and 2 for invalid
+//! arguments (reported by `clap`).
+
+use std::error::Error;
+use std::fmt;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use clap::{Parser, Subcommand};
+use rusqlite::Connection;
+use serde_derive::Serialize;
+
+use crate::db_operations::{self, Alert, Priority, Reminder};
+use crate::recurrence::Recurrence;
+use crate::settings::Settings;
+use crate::transfer::{self, Filter, Format, ImportOptions};
+use crate::tray::{NOTIFICATION_SNOOZE_MINUTES, TrayCommand};
+use crate::{alerts, debug_err, ical, instance};
+
+pub const EXIT_OK: i32 = 0;
+/// The command couldn't be carried out (database error, ...).
+pub const EXIT_FAILED: i32 = 1;
+/// No reminder with the given id.
+pub const EXIT_NOT_FOUND: i32 = 3;
+
+#[derive(Debug, Parser)]
+#[command(name = "SilliReminder", version, about = "Tray-first reminders")]
+pub struct Cli {
+    /// Start hidden in the tray.
+    #[arg(long)]
+    pub background: bool,
+
+    /// Start hidden in the tray, as launched by the system at login.
+    #[arg(long)]
+    pub autostart: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Add a reminder and print its id.
+    Add {
+        /// Due date, `YYYY-MM-DD`.
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+        /// What to be reminded of.
+        note: String,
+        /// Time of day, `HH:MM`.
+        #[arg(long, value_parser = parse_time)]
+        time: Option<NaiveTime>,
+        /// Repeat rule, e.g. `FREQ=WEEKLY;BYDAY=MO,WE`.
+        #[arg(long, value_parser = parse_recurrence)]
+        repeat: Option<Recurrence>,
+        /// Own alert lead times, e.g. `"14d, 1d, 2h"`; without it the defaults apply.
+        #[arg(long, value_parser = parse_offsets)]
+        alerts: Option<Offsets>,
+        /// `low`, `normal`, `high` or `critical`.
+        #[arg(long, value_parser = parse_priority, default_value = "normal")]
+        priority: Priority,
+    },
+    /// List active reminders.
+    List {
+        /// Print JSON instead of tab-separated lines.
+        #[arg(long)]
+        json: bool,
+        /// List completed reminders instead.
+        #[arg(long)]
+        done: bool,
+    },
+    /// Mark a reminder as done (a recurring one moves on to its next occurrence).
+    Done { id: i64 },
+    /// Delete a reminder.
+    Delete { id: i64 },
+    /// Hold back a reminder's notifications for a while (default 2h).
+    Snooze {
+        id: i64,
+        /// How long, e.g. `30m`, `2h`, `1d`.
+        #[arg(value_parser = parse_duration, conflicts_with_all = ["until", "cancel"])]
+        duration: Option<i64>,
+        /// Until a moment instead, `YYYY-MM-DD HH:MM`.
+        #[arg(long, value_parser = parse_datetime, conflicts_with = "cancel")]
+        until: Option<NaiveDateTime>,
+        /// Cancel the snooze.
+        #[arg(long)]
+        cancel: bool,
+    },
+    /// Export reminders as an iCalendar, CSV or JSON file.
+    Export {
+        /// Where to write it; `-` for standard output.
+        file: PathBuf,
+        /// `ics`, `csv` or `json`; by default from the file extension, else `ics`.
+        #[arg(long, value_parser = parse_format)]
+        format: Option<Format>,
+        /// iCalendar: write to-dos (`VTODO`) instead of events (`VEVENT`).
+        #[arg(long)]
+        todo: bool,
+        /// Export completed reminders instead of active ones.
+        #[arg(long, conflicts_with = "all")]
+        done: bool,
+        /// Export active and completed reminders.
+        #[arg(long)]
+        all: bool,
+        /// Only reminders due on or after this date, `YYYY-MM-DD`.
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+        /// Only reminders due on or before this date, `YYYY-MM-DD`.
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+    },
+    /// Import reminders from an iCalendar, CSV or JSON file.
+    Import {
+        file: PathBuf,
+        /// `ics`, `csv` or `json`; by default from the file extension.
+        #[arg(long, value_parser = parse_format)]
+        format: Option<Format>,
+        /// iCalendar: also import entries that are already over, at their own dates (CSV and
+        /// JSON rows are always imported as they are).
+        #[arg(long)]
+        include_past: bool,
+        /// Only check the file: report what would be imported and which lines are invalid.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Alert lead times in minutes, as one argument (a bare `Vec` would make `clap` expect a
+/// list of arguments).
+#[derive(Debug, Clone)]
+pub struct Offsets(Vec<i64>);
+
+#[derive(Debug)]
+struct NotFound(i64);
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no reminder with id {}", self.0)
+    }
+}
+
+impl Error for NotFound {}
+
+/// Parses the command line. On Windows, release builds have no console of their own, so
+/// the parent's console is attached first; otherwise output (and `--help`) would vanish.
+pub fn parse() -> Cli {
+    #[cfg(target_os = "windows")]
+    if std::env::args_os().len() > 1 {
+        use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+        // Fails when started from Explorer or the registry; there is nothing to print to then.
+        let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    }
+
+    Cli::parse()
+}
+
+/// Runs `command` and returns the process exit code.
+pub fn run(command: Command, settings: &Settings) -> i32 {
+    let changes_db = !matches!(
+        command,
+        Command::List { .. } | Command::Export { .. } | Command::Import { dry_run: true, .. }
+    );
+    let result = db_operations::get_db().and_then(|db| execute(command, &db.borrow(), settings));
+
+    match result {
+        Ok(()) => {
+            if changes_db {
+                notify_running_instance();
+            }
+            EXIT_OK
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            if err.is::<NotFound>() {
+                EXIT_NOT_FOUND
+            } else {
+                EXIT_FAILED
+            }
+        }
+    }
+}
+
+fn execute(command: Command, conn: &Connection, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Add {
+            date,
+            note,
+            time,
+            repeat,
+            alerts,
+            priority,
+        } => {
+            let note = note.trim();
+            if note.is_empty() {
+                return Err("note is empty".into());
+            }
+
+            let custom_alerts = alerts.is_some();
+            let offsets = alerts::effective_offsets(
+                alerts.as_ref().map_or(&settings.alert_offsets, |a| &a.0),
+                time.is_some(),
+            );
+            let alerts: Vec<Alert> = offsets
+                .into_iter()
+                .map(|offset_minutes| Alert {
+                    offset_minutes,
+                    fired_at: None,
+                })
+                .collect();
+
+            let tx = conn.unchecked_transaction()?;
+            let id = db_operations::insert_reminder(
+                &tx,
+                date,
+                time,
+                note,
+                repeat.as_ref(),
+                custom_alerts,
+                &alerts,
+            )?;
+            db_operations::set_reminder_priority(&tx, id, priority)?;
+            tx.commit()?;
+            println!("{id}");
+        }
+        Command::List { json, done } => {
+            let reminders = if done {
+                db_operations::list_completed_reminders(conn)?
+            } else {
+                db_operations::list_reminders(conn)?
+            };
+
+            // Write errors (e.g. piping into `head`) end the command instead of panicking
+            // like `println!` would.
+            let mut out = std::io::stdout().lock();
+            if json {
+                let items: Vec<ReminderJson<'_>> =
+                    reminders.iter().map(ReminderJson::from).collect();
+                serde_json::to_writer_pretty(&mut out, &items)?;
+                writeln!(out)?;
+            } else {
+                for r in &reminders {
+                    writeln!(
+                        out,
+                        "{}\t{}\t{}\t{}",
+                        r.id,
+                        r.date,
+                        r.time
+                            .map(|t| t.format("%H:%M").to_string())
+                            .unwrap_or_default(),
+                        r.note
+                    )?;
+                }
+            }
+        }
+        Command::Done { id } => {
+            let r = active_reminder(conn, id)?;
+            db_operations::complete_reminder(conn, &r, Local::now().naive_local())?;
+        }
+        Command::Delete { id } => {
+            reminder(conn, id)?;
+            db_operations::delete_reminder(conn, id)?;
+        }
+        Command::Snooze {
+            id,
+            duration,
+            until,
+            cancel,
+        } => {
+            active_reminder(conn, id)?;
+            let until = if cancel {
+                None
+            } else {
+                let minutes = duration.unwrap_or(i64::from(NOTIFICATION_SNOOZE_MINUTES));
+                Some(until.unwrap_or_else(|| {
+                    Local::now().naive_local() + chrono::Duration::minutes(minutes)
+                }))
+            };
+            db_operations::set_reminder_snoozed(conn, id, until)?;
+            if let Some(until) = until {
+                println!("{}", until.format("%Y-%m-%d %H:%M"));
+            }
+        }
+        Command::Export {
+            file,
+            format,
+            todo,
+            done,
+            all,
+            from,
+            to,
+        } => {
+            let filter = Filter {
+                done,
+                all,
+                from,
+                to,
+            };
+            let format = match format.or_else(|| Format::from_path(&file)) {
+                Some(Format::Ical(_)) | None if todo => Format::Ical(ical::Component::Todo),
+                Some(format) => format,
+                None => Format::Ical(ical::Component::Event),
+            };
+            let (text, _) = transfer::export(conn, &filter, format)?;
+
+            if file.as_os_str() == "-" {
+                std::io::stdout().lock().write_all(text.as_bytes())?;
+            } else {
+                std::fs::write(&file, text)?;
+            }
+        }
+        Command::Import {
+            file,
+            format,
+            include_past,
+            dry_run,
+        } => {
+            let format = format
+                .or_else(|| Format::from_path(&file))
+                .ok_or("unknown file type; pass --format ics, csv or json")?;
+            let parsed = transfer::parse(&std::fs::read_to_string(&file)?, format)?;
+            for err in &parsed.errors {
+                eprintln!("{}: {err}", file.display());
+            }
+
+            let options = ImportOptions {
+                keep_past: include_past || !matches!(format, Format::Ical(_)),
+                dry_run,
+            };
+            let report = transfer::import(conn, parsed.items, settings, options)?;
+
+            if dry_run {
+                println!("dry run, nothing was changed");
+            }
+            println!("imported: {}", report.imported);
+            println!("already present: {}", report.duplicates);
+            println!("skipped as past: {}", report.past);
+            for rule in &report.unsupported_rules {
+                println!("imported without repeating (unsupported rule): {rule}");
+            }
+            println!("invalid: {}", parsed.errors.len());
+        }
+    }
+    Ok(())
+}
+
+/// Lets a running app pick up the change now rather than at its next reminder check.
+fn notify_running_instance() {
+    if instance::is_running()
+        && let Err(err) = instance::send(TrayCommand::Reload)
+    {
+        debug_err!("failed to notify the running instance: {err}");
+    }
+}
+
+fn reminder(conn: &Connection, id: i64) -> Result<Reminder, Box<dyn Error>> {
+    db_operations::get_reminder(conn, id)?.ok_or_else(|| NotFound(id).into())
+}
+
+/// Like [`reminder`], but completed reminders count as missing.
+fn active_reminder(conn: &Connection, id: i64) -> Result<Reminder, Box<dyn Error>> {
+    let r = reminder(conn, id)?;
+    if r.completed_at.is_some() {
+        return Err(format!("reminder {id} is already done").into());
+    }
+    Ok(r)
+}
+
+/// One reminder in `list --json`.
+#[derive(Serialize)]
+struct ReminderJson<'a> {
+    id: i64,
+    date: String,
+    time: Option<String>,
+    note: &'a str,
+    recurrence: Option<String>,
+    priority: &'static str,
+    /// Lead times in minutes, earliest alert first.
+    alerts: Vec<i64>,
+    snoozed_until: Option<String>,
+    completed_at: Option<String>,
+}
+
+impl<'a> From<&'a Reminder> for ReminderJson<'a> {
+    fn from(r: &'a Reminder) -> Self {
+        Self {
+            id: r.id,
+            date: r.date.to_string(),
+            time: r.time.map(|t| t.format("%H:%M").to_string()),
+            note: &r.note,
+            recurrence: r.recurrence.as_ref().map(Recurrence::to_string),
+            priority: r.priority.name(),
+            alerts: r.alerts.iter().map(|a| a.offset_minutes).collect(),
+            snoozed_until: r
+                .snoozed_until
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+            completed_at: r
+                .completed_at
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string()),
+        }
+    }
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| "expected YYYY-MM-DD".to_owned())
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| "expected HH:MM".to_owned())
+}
+
+fn parse_datetime(s: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+        .map_err(|_| "expected \"YYYY-MM-DD HH:MM\"".to_owned())
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "ics" | "ical" | "icalendar" => Ok(Format::Ical(ical::Component::Event)),
+        "csv" => Ok(Format::Csv),
+        "json" => Ok(Format::Json),
+        _ => Err("expected ics, csv or json".to_owned()),
+    }
+}
+
+fn parse_recurrence(s: &str) -> Result<Recurrence, String> {
+    s.parse::<Recurrence>().map_err(|err| err.to_string())
+}
+
+fn parse_priority(s: &str) -> Result<Priority, String> {
+    Priority::from_name(s).ok_or_else(|| "expected low, normal, high or critical".to_owned())
+}
+
+fn parse_offsets(s: &str) -> Result<Offsets, String> {
+    alerts::parse_offsets(s)
+        .map(Offsets)
+        .map_err(|err| err.to_string())
+}
+
+/// A single lead time such as `2h`, in minutes.
+fn parse_duration(s: &str) -> Result<i64, String> {
+    match parse_offsets(s)?.0.as_slice() {
+        [minutes] if *minutes > 0 => Ok(*minutes),
+        _ => Err("expected one duration such as 30m, 2h or 1d".to_owned()),
+    }
+}
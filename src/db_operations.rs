@@ -1,29 +1,50 @@
 //! Database access layer.
 //!
 //! Split overview:
-//! - `types.rs`: DB-facing domain types (`Reminder`) + parsing helpers
-//! - `schema.rs`: schema creation/migrations
+//! - `types.rs`: DB-facing domain types (`Reminder`, `Alert`, `Tag`, `Priority`) + parsing helpers
+//! - `schema.rs`: versioned schema migrations
 //! - `queries.rs`: read/query helpers
 //! - `insert.rs`, `update.rs`, `delete.rs`: write helpers
 //! - `path.rs`: DB file location
 //! - `connection.rs`: open connection + ensure schema
+//! - `backup.rs`: automatic backups and restoring them
+//! - `recovery.rs`: integrity check + salvaging a damaged database
 
+mod backup;
 mod connection;
 mod delete;
 mod insert;
 mod path;
 mod queries;
+mod recovery;
 mod schema;
 mod types;
 mod update;
 
+pub use backup::{
+    BackupInfo, BackupKind, backup_on_startup, list_backups, restore_backup, restore_backup_file,
+};
 pub use connection::get_db;
-pub use delete::delete_reminder;
-pub use insert::insert_reminder;
-pub use queries::list_reminders;
-pub use types::Reminder;
-pub use update::set_reminder_notified_level;
+#[cfg(test)]
+pub use connection::memory_db;
+pub use delete::{delete_reminder, delete_tag};
+pub use insert::{insert_completed_copy, insert_reminder, insert_tag};
+pub use queries::{
+    find_reminder_by_uid, get_reminder, list_completed_reminders, list_reminders, list_tags,
+};
+pub use recovery::{CorruptDatabaseError, recover_db};
+pub use schema::NewerSchemaError;
+pub use types::{Alert, Priority, Reminder, TAG_COLORS, Tag};
+pub use update::{
+    complete_reminder, roll_reminder_forward, set_alert_fired, set_reminder_alerts,
+    set_reminder_completed, set_reminder_overdue_notified, set_reminder_priority,
+    set_reminder_snoozed, set_reminder_tags, set_reminder_uid, update_reminder, update_tag,
+};
 // More helpers exist in submodules (delete/update/get) when needed.
 
 // Internal-only items shared across db submodules.
-pub(in crate::db_operations) use types::parse_db_date;
+pub(in crate::db_operations) use types::{
+    format_db_color, format_db_datetime, format_db_offsets, format_db_priority, format_db_time,
+    parse_db_color, parse_db_date, parse_db_datetime, parse_db_offsets, parse_db_optional_date,
+    parse_db_priority, parse_db_recurrence, parse_db_time,
+};
@@ -0,0 +1,261 @@
+//! Automatic copies of the database in `data/backups`.
+//!
+//! Copies are made with SQLite's online backup API, so they are consistent even while
+//! another connection is writing. Each file is named
+//! `silli_reminder-YYYYMMDD-HHMMSS.mmm-<kind>.db` (older ones without the milliseconds), so
+//! when and why it was made can be read without opening it.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime};
+use rusqlite::{Connection, MAIN_DB, OpenFlags, backup::Progress};
+
+use super::{path, recovery, schema};
+use crate::debug_log;
+
+const PREFIX: &str = "silli_reminder-";
+/// Down to the millisecond, so two backups of the same kind made within one second (e.g. on
+/// startup and right before an upgrade) don't overwrite each other.
+const STAMP: &str = "%Y%m%d-%H%M%S%.3f";
+/// Parses [`STAMP`] as well as the whole seconds older backups were named with.
+const STAMP_PARSE: &str = "%Y%m%d-%H%M%S%.f";
+
+/// Why a backup was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupKind {
+    /// When the app started.
+    Startup,
+    /// Right before a schema migration.
+    BeforeUpgrade,
+    /// Right before another backup was restored over the database.
+    BeforeRestore,
+}
+
+impl BackupKind {
+    const ALL: [BackupKind; 3] = [Self::Startup, Self::BeforeUpgrade, Self::BeforeRestore];
+
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Startup => "startup",
+            Self::BeforeUpgrade => "upgrade",
+            Self::BeforeRestore => "restore",
+        }
+    }
+}
+
+/// A backup file, as listed for restoring.
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+    pub kind: BackupKind,
+    /// How many reminders (active and done) it holds; `None` if it couldn't be read.
+    pub reminders: Option<i64>,
+    /// Made by a newer version of the app, so it can't be restored.
+    pub too_new: bool,
+}
+
+/// Copies the database to a new file in the backups directory.
+pub(super) fn create_backup(
+    conn: &Connection,
+    kind: BackupKind,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = path::backups_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let target = loop {
+        let stamp = Local::now().naive_local().format(STAMP);
+        let target = dir.join(format!("{PREFIX}{stamp}-{}.db", kind.tag()));
+        if !target.exists() {
+            break target;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    conn.backup(MAIN_DB, &target, None)?;
+    debug_log!("backed up the database to {}", target.display());
+    Ok(target)
+}
+
+/// The startup backup: made unless the database has no reminders (so an emptied database
+/// doesn't push the useful copies out), then the oldest backups beyond `keep` are removed.
+pub fn backup_on_startup(conn: &Connection, keep: usize) -> Result<(), Box<dyn Error>> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM `Reminder`;", [], |row| row.get(0))?;
+    if count > 0 {
+        create_backup(conn, BackupKind::Startup)?;
+    }
+    prune_backups(keep)
+}
+
+/// Removes all but the `keep` newest backups (at least one is always kept).
+fn prune_backups(keep: usize) -> Result<(), Box<dyn Error>> {
+    for old in list_backup_files()?.into_iter().skip(keep.max(1)) {
+        debug_log!("removing old backup {}", old.path.display());
+        std::fs::remove_file(&old.path)?;
+    }
+    Ok(())
+}
+
+/// The backups, newest first.
+pub fn list_backups() -> Result<Vec<BackupInfo>, Box<dyn Error>> {
+    let mut backups = list_backup_files()?;
+    for backup in &mut backups {
+        if let Ok((version, count)) = inspect(&backup.path) {
+            backup.reminders = Some(count);
+            backup.too_new = version > schema::SCHEMA_VERSION;
+        }
+    }
+    Ok(backups)
+}
+
+/// Replaces the database's contents with the backup at `path`, then migrates it to the
+/// current schema. The current state is backed up first, so a restore can be undone.
+pub fn restore_backup(conn: &mut Connection, path: &Path) -> Result<(), Box<dyn Error>> {
+    let (version, _) = inspect(path)?;
+    if version > schema::SCHEMA_VERSION {
+        return Err(schema::NewerSchemaError {
+            found: version,
+            supported: schema::SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    create_backup(conn, BackupKind::BeforeRestore)?;
+    conn.restore(MAIN_DB, path, None::<fn(Progress)>)?;
+    debug_log!("restored the database from {}", path.display());
+    schema::ensure_schema(conn)
+}
+
+/// Puts a copy of the backup at `path` in place of a damaged database, which is moved to
+/// the quarantine directory first (see [`recovery`]). The connection to the database must
+/// be closed.
+pub fn restore_backup_file(path: &Path) -> Result<(), Box<dyn Error>> {
+    let (version, _) = inspect(path)?;
+    if version > schema::SCHEMA_VERSION {
+        return Err(schema::NewerSchemaError {
+            found: version,
+            supported: schema::SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let db_path = path::db_path();
+    if db_path.exists() {
+        // Only a damaged file is moved aside. One that is locked or can't be read for
+        // another reason stays where it is, and so does the error.
+        let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        match recovery::check_integrity(&conn) {
+            Err(err) if err.is::<recovery::CorruptDatabaseError>() => {}
+            Err(err) => return Err(err),
+            Ok(()) => return Err("the database isn't damaged, so it is left in place".into()),
+        }
+        drop(conn);
+    }
+    if let Some(quarantined) = recovery::quarantine(&db_path)? {
+        debug_log!("moved the damaged database to {}", quarantined.display());
+    }
+    std::fs::copy(path, &db_path)?;
+    debug_log!("restored the database from {}", path.display());
+    Ok(())
+}
+
+/// Backup files with the time and kind from their names (and nothing read from them yet),
+/// newest first.
+fn list_backup_files() -> Result<Vec<BackupInfo>, Box<dyn Error>> {
+    let dir = path::backups_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((stamp, tag)) = name
+            .strip_prefix(PREFIX)
+            .and_then(|rest| rest.strip_suffix(".db"))
+            .and_then(|rest| rest.rsplit_once('-'))
+        else {
+            continue;
+        };
+        let Some(kind) = BackupKind::ALL.into_iter().find(|k| k.tag() == tag) else {
+            continue;
+        };
+        if let Ok(created) = NaiveDateTime::parse_from_str(stamp, STAMP_PARSE) {
+            files.push(BackupInfo {
+                path,
+                created,
+                kind,
+                reminders: None,
+                too_new: false,
+            });
+        }
+    }
+    files.sort_by_key(|b| std::cmp::Reverse(b.created));
+    Ok(files)
+}
+
+/// The schema version and number of reminders of a backup, read without changing it.
+fn inspect(path: &Path) -> rusqlite::Result<(i32, i64)> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let version = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let count = conn.query_row("SELECT COUNT(*) FROM `Reminder`;", [], |row| row.get(0))?;
+    Ok((version, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Timelike as _};
+
+    use super::super::insert_reminder;
+    use super::super::path::TestDataDir;
+    use super::*;
+
+    #[test]
+    fn stamps_parse_with_and_without_milliseconds() {
+        let at = NaiveDateTime::parse_from_str("2026-10-17 09:05:03.042", "%Y-%m-%d %H:%M:%S%.f")
+            .unwrap();
+        let stamp = at.format(STAMP).to_string();
+        assert_eq!(stamp, "20261017-090503.042");
+        assert_eq!(NaiveDateTime::parse_from_str(&stamp, STAMP_PARSE), Ok(at));
+
+        let old = NaiveDateTime::parse_from_str("20261017-090503", STAMP_PARSE).unwrap();
+        assert_eq!(old, at.with_nanosecond(0).unwrap());
+    }
+
+    #[test]
+    fn restores_a_backup_file_only_over_a_damaged_database() {
+        let _dir = TestDataDir::new("restore");
+        let db_path = path::db_path();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let conn = Connection::open(&db_path).unwrap();
+        schema::ensure_schema(&conn).unwrap();
+        insert_reminder(&conn, date, None, "backed up", None, false, &[]).unwrap();
+        let backup = create_backup(&conn, BackupKind::Startup).unwrap();
+        insert_reminder(&conn, date, None, "newer", None, false, &[]).unwrap();
+        drop(conn);
+        let reminders = |path: &Path| inspect(path).map(|(_, count)| count).unwrap();
+
+        let err = restore_backup_file(&backup).unwrap_err();
+        assert!(err.to_string().contains("isn't damaged"), "{err}");
+        assert_eq!(reminders(&db_path), 2);
+        assert!(!path::quarantine_dir().exists());
+
+        // Everything after the file header is gone.
+        let mut bytes = std::fs::read(&db_path).unwrap();
+        bytes[100..].fill(0);
+        std::fs::write(&db_path, &bytes).unwrap();
+
+        restore_backup_file(&backup).unwrap();
+        assert_eq!(reminders(&db_path), 1);
+        let quarantined: Vec<_> = std::fs::read_dir(path::quarantine_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(std::fs::read(&quarantined[0]).unwrap(), bytes);
+    }
+}
@@ -1,16 +1,29 @@
+use std::error::Error;
 use std::{cell::RefCell, rc::Rc};
 
 use rusqlite::Connection;
 
-use super::{path, schema};
+use super::{path, recovery, schema};
 
-pub fn get_db() -> rusqlite::Result<Rc<RefCell<Connection>>> {
+/// Opens the database, checks it for damage and migrates it to the current schema. Fails with
+/// [`NewerSchemaError`](super::NewerSchemaError) for a database from a newer app version and
+/// with [`CorruptDatabaseError`](super::CorruptDatabaseError) for a damaged one.
+pub fn get_db() -> Result<Rc<RefCell<Connection>>, Box<dyn Error>> {
     let db_path = path::db_path();
     if let Some(parent) = db_path.parent() {
         let _ = std::fs::create_dir_all(parent);
     }
 
     let conn = Connection::open(db_path)?;
+    recovery::check_integrity(&conn)?;
     schema::ensure_schema(&conn)?;
     Ok(Rc::new(RefCell::new(conn)))
 }
+
+/// A fresh in-memory database with the current schema, for tests.
+#[cfg(test)]
+pub fn memory_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    schema::ensure_schema(&conn).unwrap();
+    conn
+}
@@ -6,3 +6,9 @@ pub fn delete_reminder(conn: &Connection, id: i64) -> Result<(), Box<dyn Error>>
     conn.execute("DELETE FROM `Reminder` WHERE `id` = ?1;", (&id,))?;
     Ok(())
 }
+
+/// Deletes a tag; its reminders just lose it.
+pub fn delete_tag(conn: &Connection, id: i64) -> Result<(), Box<dyn Error>> {
+    conn.execute("DELETE FROM `Tag` WHERE `id` = ?1;", (&id,))?;
+    Ok(())
+}
@@ -1,16 +1,85 @@
 use std::error::Error;
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use rusqlite::{Connection, params};
 
+use super::schema::NEW_UID;
+use super::{
+    Alert, Reminder, format_db_color, format_db_datetime, format_db_offsets, format_db_priority,
+    format_db_time, set_reminder_alerts, set_reminder_tags,
+};
+use crate::recurrence::Recurrence;
+
+/// Inserts a new reminder with its `alerts` (`custom_alerts`: picked for this reminder rather
+/// than taken from the global defaults).
 pub fn insert_reminder(
     conn: &Connection,
     date: NaiveDate,
+    time: Option<NaiveTime>,
     note: &str,
+    recurrence: Option<&Recurrence>,
+    custom_alerts: bool,
+    alerts: &[Alert],
+) -> Result<i64, Box<dyn Error>> {
+    conn.execute(
+        &format!(
+            "INSERT INTO `Reminder` (`date`, `time`, `note`, `recurrence`, `uid`)
+             VALUES (?1, ?2, ?3, ?4, {NEW_UID});"
+        ),
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            format_db_time(time),
+            note,
+            recurrence.map(|r| r.to_string()),
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    set_reminder_alerts(conn, id, custom_alerts, alerts)?;
+    Ok(id)
+}
+
+/// Records a finished occurrence of a recurring reminder as its own completed,
+/// non-repeating row, so the series itself can move on to the next occurrence. The copy keeps
+/// the occurrence's alerts and tags, so restoring it gives a reminder that still notifies.
+pub fn insert_completed_copy(
+    conn: &Connection,
+    reminder: &Reminder,
+    completed_at: NaiveDateTime,
+) -> Result<i64, Box<dyn Error>> {
+    conn.execute(
+        &format!(
+            "INSERT INTO `Reminder` (`date`, `time`, `note`, `completed_at`, `priority`, `uid`)
+             VALUES (?1, ?2, ?3, ?4, ?5, {NEW_UID});"
+        ),
+        params![
+            reminder.date.format("%Y-%m-%d").to_string(),
+            format_db_time(reminder.time),
+            reminder.note,
+            format_db_datetime(Some(completed_at)),
+            format_db_priority(reminder.priority),
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    set_reminder_alerts(conn, id, reminder.custom_alerts, &reminder.alerts)?;
+    let tag_ids: Vec<i64> = reminder.tags.iter().map(|t| t.id).collect();
+    set_reminder_tags(conn, id, &tag_ids)?;
+    Ok(id)
+}
+
+/// Adds a tag; fails when one with the same name (ignoring case) exists.
+pub fn insert_tag(
+    conn: &Connection,
+    name: &str,
+    color: [u8; 3],
+    alert_offsets: Option<&[i64]>,
 ) -> Result<i64, Box<dyn Error>> {
     conn.execute(
-        "INSERT INTO `Reminder` (`date`, `note`) VALUES (?1, ?2);",
-        params![date.format("%Y-%m-%d").to_string(), note],
+        "INSERT INTO `Tag` (`name`, `color`, `alert_offsets`) VALUES (?1, ?2, ?3);",
+        params![
+            name,
+            format_db_color(color),
+            format_db_offsets(alert_offsets)
+        ],
     )?;
     Ok(conn.last_insert_rowid())
 }
@@ -1,7 +1,56 @@
 use std::path::PathBuf;
 
+#[cfg(test)]
+thread_local! {
+    /// Where a test keeps its database files instead of `data` (each test has its own thread).
+    static TEST_DATA_DIR: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+fn data_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = TEST_DATA_DIR.with_borrow(Clone::clone) {
+        return dir;
+    }
+
+    crate::paths::app_data_dir().join("data")
+}
+
 pub(super) fn db_path() -> PathBuf {
-    crate::paths::app_data_dir()
-        .join("data")
-        .join("silli_reminder.db")
+    data_dir().join("silli_reminder.db")
+}
+
+/// Where automatic backups go (see [`super::backup`]).
+pub(super) fn backups_dir() -> PathBuf {
+    data_dir().join("backups")
+}
+
+/// Where damaged databases are moved (see [`super::recovery`]).
+pub(super) fn quarantine_dir() -> PathBuf {
+    data_dir().join("quarantine")
+}
+
+/// Points the current test's database files at a new temporary directory, removed again
+/// when this is dropped.
+#[cfg(test)]
+pub(super) struct TestDataDir(PathBuf);
+
+#[cfg(test)]
+impl TestDataDir {
+    pub(super) fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("silli_reminder-test-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        TEST_DATA_DIR.set(Some(dir.clone()));
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDataDir {
+    fn drop(&mut self) {
+        TEST_DATA_DIR.set(None);
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
 }
@@ -1,55 +1,181 @@
 use std::error::Error;
 
-use rusqlite::Connection;
+use std::collections::HashMap;
 
-use super::{Reminder, parse_db_date};
+use rusqlite::{Connection, Row};
 
-pub fn list_reminders(conn: &Connection) -> Result<Vec<Reminder>, Box<dyn Error>> {
+use super::{
+    Alert, Reminder, Tag, parse_db_color, parse_db_date, parse_db_datetime, parse_db_offsets,
+    parse_db_optional_date, parse_db_priority, parse_db_recurrence, parse_db_time,
+};
+
+/// Column list shared by every `Reminder` query; keep in sync with [`reminder_from_row`].
+const REMINDER_COLUMNS: &str = "`id`, `date`, `time`, `note`, `custom_alerts`, `recurrence`, `completed_at`, \
+     `overdue_notified_on`, `snoozed_until`, `uid`, `priority`";
+
+fn reminder_from_row(row: &Row<'_>) -> rusqlite::Result<Reminder> {
+    let id: i64 = row.get(0)?;
+    let date_str: String = row.get(1)?;
+    let time_str: Option<String> = row.get(2)?;
+    let note: String = row.get(3)?;
+    let custom_alerts: bool = row.get(4)?;
+    let recurrence: Option<String> = row.get(5)?;
+    let completed_at: Option<String> = row.get(6)?;
+    let overdue_notified_on: Option<String> = row.get(7)?;
+    let snoozed_until: Option<String> = row.get(8)?;
+    let uid: Option<String> = row.get(9)?;
+    let priority: i64 = row.get(10)?;
+
+    Ok(Reminder {
+        id,
+        date: parse_db_date(&date_str)?,
+        time: parse_db_time(time_str)?,
+        note,
+        recurrence: parse_db_recurrence(recurrence)?,
+        completed_at: parse_db_datetime(completed_at)?,
+        overdue_notified_on: parse_db_optional_date(overdue_notified_on)?,
+        snoozed_until: parse_db_datetime(snoozed_until)?,
+        custom_alerts,
+        alerts: Vec::new(),
+        uid,
+        tags: Vec::new(),
+        priority: parse_db_priority(priority)?,
+    })
+}
+
+/// Column list shared by every `Tag` query; keep in sync with [`tag_from_row`].
+const TAG_COLUMNS: &str = "`Tag`.`id`, `Tag`.`name`, `Tag`.`color`, `Tag`.`alert_offsets`";
+
+fn tag_from_row(row: &Row<'_>) -> rusqlite::Result<Tag> {
+    let color: String = row.get(2)?;
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: parse_db_color(&color)?,
+        alert_offsets: parse_db_offsets(row.get(3)?)?,
+    })
+}
+
+/// Fills in `alerts` of each reminder in `reminders` (one query for all of them).
+fn attach_alerts(conn: &Connection, reminders: &mut [Reminder]) -> Result<(), Box<dyn Error>> {
     let mut stmt = conn.prepare(
-        "SELECT `id`, `date`, `note`, `notified_level`
-         FROM `Reminder`
-         ORDER BY `date` ASC, `id` ASC;",
+        "SELECT `reminder_id`, `offset_minutes`, `fired_at`
+         FROM `ReminderAlert`
+         ORDER BY `reminder_id` ASC, `offset_minutes` DESC;",
     )?;
 
-    let iter = stmt.query_map([], |row| {
-        let id: i64 = row.get(0)?;
-        let date_str: String = row.get(1)?;
-        let note: String = row.get(2)?;
-        let notified_level: i64 = row.get(3)?;
+    let mut by_reminder: HashMap<i64, Vec<Alert>> = HashMap::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let reminder_id: i64 = row.get(0)?;
+        let fired_at: Option<String> = row.get(2)?;
+        by_reminder.entry(reminder_id).or_default().push(Alert {
+            offset_minutes: row.get(1)?,
+            fired_at: parse_db_datetime(fired_at)?,
+        });
+    }
 
-        Ok(Reminder {
-            id,
-            date: parse_db_date(&date_str)?,
-            note,
-            notified_level: notified_level.clamp(0, 3) as u8,
-        })
-    })?;
+    for r in reminders {
+        r.alerts = by_reminder.remove(&r.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Fills in `tags` of each reminder in `reminders` (one query for all of them).
+fn attach_tags(conn: &Connection, reminders: &mut [Reminder]) -> Result<(), Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {TAG_COLUMNS}, `ReminderTag`.`reminder_id`
+         FROM `ReminderTag` JOIN `Tag` ON `Tag`.`id` = `ReminderTag`.`tag_id`
+         ORDER BY `Tag`.`name` ASC;"
+    ))?;
+
+    let mut by_reminder: HashMap<i64, Vec<Tag>> = HashMap::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let reminder_id: i64 = row.get(4)?;
+        by_reminder
+            .entry(reminder_id)
+            .or_default()
+            .push(tag_from_row(row)?);
+    }
+
+    for r in reminders {
+        r.tags = by_reminder.remove(&r.id).unwrap_or_default();
+    }
+    Ok(())
+}
 
-    Ok(iter.collect::<rusqlite::Result<Vec<_>>>()?)
+/// Every tag, ordered by name.
+pub fn list_tags(conn: &Connection) -> Result<Vec<Tag>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {TAG_COLUMNS} FROM `Tag` ORDER BY `name` ASC;"
+    ))?;
+    let tags = stmt
+        .query_map([], tag_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(tags)
+}
+
+/// Active (not completed) reminders.
+pub fn list_reminders(conn: &Connection) -> Result<Vec<Reminder>, Box<dyn Error>> {
+    // On the same day, higher priorities come first; within a priority, untimed reminders
+    // (NULL time) sort before timed ones.
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {REMINDER_COLUMNS}
+         FROM `Reminder`
+         WHERE `completed_at` IS NULL
+         ORDER BY `date` ASC, `priority` DESC, `time` ASC, `id` ASC;"
+    ))?;
+
+    let iter = stmt.query_map([], reminder_from_row)?;
+
+    let mut reminders = iter.collect::<rusqlite::Result<Vec<_>>>()?;
+    attach_alerts(conn, &mut reminders)?;
+    attach_tags(conn, &mut reminders)?;
+    Ok(reminders)
+}
+
+/// Completed reminders, most recently completed first.
+pub fn list_completed_reminders(conn: &Connection) -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {REMINDER_COLUMNS}
+         FROM `Reminder`
+         WHERE `completed_at` IS NOT NULL
+         ORDER BY `completed_at` DESC, `id` DESC;"
+    ))?;
+
+    let iter = stmt.query_map([], reminder_from_row)?;
+
+    let mut reminders = iter.collect::<rusqlite::Result<Vec<_>>>()?;
+    attach_alerts(conn, &mut reminders)?;
+    attach_tags(conn, &mut reminders)?;
+    Ok(reminders)
 }
 
-#[allow(dead_code)]
 pub fn get_reminder(conn: &Connection, id: i64) -> Result<Option<Reminder>, Box<dyn Error>> {
-    let mut stmt = conn.prepare(
-        "SELECT `id`, `date`, `note`, `notified_level`
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {REMINDER_COLUMNS}
          FROM `Reminder`
-         WHERE `id` = ?1;",
-    )?;
+         WHERE `id` = ?1;"
+    ))?;
 
     let mut rows = stmt.query([id])?;
     let Some(row) = rows.next()? else {
         return Ok(None);
     };
 
-    let id: i64 = row.get(0)?;
-    let date_str: String = row.get(1)?;
-    let note: String = row.get(2)?;
-    let notified_level: i64 = row.get(3)?;
+    let mut reminder = reminder_from_row(row)?;
+    attach_alerts(conn, std::slice::from_mut(&mut reminder))?;
+    attach_tags(conn, std::slice::from_mut(&mut reminder))?;
+    Ok(Some(reminder))
+}
 
-    Ok(Some(Reminder {
-        id,
-        date: parse_db_date(&date_str)?,
-        note,
-        notified_level: notified_level.clamp(0, 3) as u8,
-    }))
+/// The id of the reminder with `uid`, if there is one.
+pub fn find_reminder_by_uid(conn: &Connection, uid: &str) -> Result<Option<i64>, Box<dyn Error>> {
+    let mut stmt = conn.prepare("SELECT `id` FROM `Reminder` WHERE `uid` = ?1;")?;
+    let mut rows = stmt.query([uid])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
 }
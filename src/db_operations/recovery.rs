@@ -0,0 +1,417 @@
+//! Detecting a damaged database and getting back what can still be read.
+//!
+//! [`check_integrity`] runs on every open, before migrations or backups touch the file.
+//! [`recover_db`] rebuilds the database from the damaged file's own schema and copies
+//! every row it can read into it, one row at a time, so a bad page only costs the rows on
+//! it. The damaged file is never deleted; it is moved to `data/quarantine`.
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use rusqlite::types::Value;
+use rusqlite::{Connection, ErrorCode, params_from_iter};
+
+use super::path;
+use crate::{debug_err, debug_log};
+
+/// How many `integrity_check` messages are kept; a badly damaged file reports thousands.
+const MAX_PROBLEMS: usize = 20;
+
+/// The database file is damaged (or isn't a database at all).
+#[derive(Debug)]
+pub struct CorruptDatabaseError {
+    /// What SQLite found, e.g. `row 12 missing from index idx_reminder_date`.
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for CorruptDatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the database is damaged")?;
+        if let Some(first) = self.problems.first() {
+            write!(f, " ({first})")?;
+        }
+        write!(f, "; open SilliReminder to recover it")
+    }
+}
+
+impl Error for CorruptDatabaseError {}
+
+/// Runs `PRAGMA integrity_check`; fails with [`CorruptDatabaseError`] unless it reports `ok`.
+pub(super) fn check_integrity(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    let problems = conn
+        .prepare("PRAGMA integrity_check;")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(0))?
+                .take(MAX_PROBLEMS)
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|err| match err.sqlite_error_code() {
+            Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase) => {
+                Box::new(CorruptDatabaseError {
+                    problems: vec![err.to_string()],
+                }) as Box<dyn Error>
+            }
+            _ => err.into(),
+        })?;
+
+    if problems.len() == 1 && problems[0] == "ok" {
+        return Ok(());
+    }
+    Err(CorruptDatabaseError { problems }.into())
+}
+
+/// What [`recover_db`] got back.
+#[derive(Debug)]
+pub struct RecoveryReport {
+    /// Reminders (active and done) copied to the new database.
+    pub reminders: usize,
+    /// Every row was read; only indexes or free pages were damaged.
+    pub complete: bool,
+    /// Where the damaged file went.
+    pub quarantined: PathBuf,
+}
+
+/// Replaces the damaged database with a new one holding everything that could be read
+/// from it. The connection to the database must be closed.
+pub fn recover_db() -> Result<RecoveryReport, Box<dyn Error>> {
+    let db_path = path::db_path();
+    let fresh_path = db_path.with_extension("db.recovering");
+    let _ = std::fs::remove_file(&fresh_path);
+
+    let (reminders, complete) = {
+        let salvaged = Connection::open(&db_path).and_then(|damaged| {
+            let fresh = Connection::open(&fresh_path)?;
+            salvage(&damaged, &fresh)
+        });
+        match salvaged {
+            Ok(counts) => counts,
+            Err(err) => {
+                // Not even the schema could be read; start from an empty database.
+                debug_err!("nothing could be salvaged: {err}");
+                std::fs::remove_file(&fresh_path)?;
+                (0, false)
+            }
+        }
+    };
+
+    let quarantined = quarantine(&db_path)?.ok_or("the database file is missing")?;
+    if fresh_path.exists() {
+        std::fs::rename(&fresh_path, &db_path)?;
+    }
+    debug_log!(
+        "recovered {reminders} reminders (complete: {complete}); damaged file moved to {}",
+        quarantined.display()
+    );
+    Ok(RecoveryReport {
+        reminders,
+        complete,
+        quarantined,
+    })
+}
+
+/// Moves the database file (and its journal) to the quarantine directory, so a new one can
+/// take its place. Returns where it went, or `None` when there was no file.
+pub(super) fn quarantine(db_path: &Path) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+
+    let dir = path::quarantine_dir();
+    std::fs::create_dir_all(&dir)?;
+    let stamp = Local::now().naive_local().format("%Y%m%d-%H%M%S");
+    let target = dir.join(format!("silli_reminder-{stamp}.db"));
+    std::fs::rename(db_path, &target)?;
+
+    // A leftover journal belongs to the damaged file; next to a new one it would be
+    // replayed into it.
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let mut side = db_path.as_os_str().to_owned();
+        side.push(suffix);
+        let side = PathBuf::from(side);
+        if side.exists() {
+            let mut moved = target.as_os_str().to_owned();
+            moved.push(suffix);
+            std::fs::rename(&side, PathBuf::from(moved))?;
+        }
+    }
+    Ok(Some(target))
+}
+
+/// Recreates `damaged`'s tables and indexes in `fresh` and copies the readable rows.
+/// Returns the number of reminders copied and whether every row could be read.
+fn salvage(damaged: &Connection, fresh: &Connection) -> rusqlite::Result<(usize, bool)> {
+    let version: i32 = damaged.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let schema = {
+        let mut stmt = damaged.prepare(
+            "SELECT `type`, `name`, `sql` FROM `sqlite_master`
+             WHERE `sql` IS NOT NULL AND `name` NOT LIKE 'sqlite_%'
+             ORDER BY `type` = 'table' DESC;",
+        )?;
+        stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    // Rows are copied table by table, children possibly before their parents, and links
+    // to lost rows are only removed at the end.
+    fresh.pragma_update(None, "foreign_keys", false)?;
+    let tx = fresh.unchecked_transaction()?;
+    // Indexes first too: a damaged unique index may have let duplicates in, and
+    // `INSERT OR IGNORE` below keeps the first of them.
+    for (_, _, sql) in &schema {
+        tx.execute(sql, ())?;
+    }
+
+    let mut reminders = 0;
+    let mut complete = true;
+    for (kind, table, _) in &schema {
+        if kind != "table" {
+            continue;
+        }
+        let (copied, all) = copy_rows(damaged, &tx, table)?;
+        complete &= all;
+        if table == "Reminder" {
+            reminders = copied;
+        }
+    }
+
+    // Alerts and tag links of reminders (or tags) that were lost.
+    let orphans = [
+        (
+            "ReminderAlert",
+            "DELETE FROM `ReminderAlert`
+             WHERE `reminder_id` NOT IN (SELECT `id` FROM `Reminder`);",
+        ),
+        (
+            "ReminderTag",
+            "DELETE FROM `ReminderTag`
+             WHERE `reminder_id` NOT IN (SELECT `id` FROM `Reminder`)
+                OR `tag_id` NOT IN (SELECT `id` FROM `Tag`);",
+        ),
+    ];
+    for (table, sql) in orphans {
+        if schema.iter().any(|(_, name, _)| name == table) {
+            tx.execute(sql, ())?;
+        }
+    }
+    copy_sequences(damaged, &tx)?;
+    tx.pragma_update(None, "user_version", version)?;
+    tx.commit()?;
+    Ok((reminders, complete))
+}
+
+/// Carries the `AUTOINCREMENT` counters over, so the ids of lost rows aren't handed out
+/// again: exports and other copies may still refer to them. Only what was copied sets the
+/// counters if `damaged`'s can't be read.
+fn copy_sequences(damaged: &Connection, fresh: &Connection) -> rusqlite::Result<()> {
+    let sequences = damaged
+        .prepare("SELECT `name`, `seq` FROM `sqlite_sequence`;")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        });
+    let sequences = match sequences {
+        Ok(sequences) => sequences,
+        Err(err) => {
+            debug_err!("the id counters are unreadable: {err}");
+            return Ok(());
+        }
+    };
+
+    for (table, seq) in sequences {
+        let updated = fresh.execute(
+            "UPDATE `sqlite_sequence` SET `seq` = max(`seq`, ?2) WHERE `name` = ?1;",
+            (&table, seq),
+        )?;
+        if updated == 0 {
+            fresh.execute(
+                "INSERT INTO `sqlite_sequence` (`name`, `seq`) VALUES (?1, ?2);",
+                (&table, seq),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies `table` row by row. Returns how many rows were copied and whether all of them
+/// could be read.
+fn copy_rows(
+    damaged: &Connection,
+    fresh: &Connection,
+    table: &str,
+) -> rusqlite::Result<(usize, bool)> {
+    let columns: Vec<String> = {
+        let mut stmt = fresh.prepare(&format!("PRAGMA table_info(`{table}`);"))?;
+        stmt.query_map([], |row| row.get(1))?
+            .collect::<rusqlite::Result<_>>()?
+    };
+    let column_list = columns
+        .iter()
+        .map(|c| format!("`{c}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Walk the rowids from both ends: a damaged page stops a scan, but the rows behind it
+    // can still be reached from the other side.
+    let mut complete = true;
+    let mut rowids = Vec::new();
+    for order in ["ASC", "DESC"] {
+        let scanned = damaged
+            .prepare(&format!(
+                "SELECT `rowid` FROM `{table}` ORDER BY `rowid` {order};"
+            ))
+            .and_then(|mut stmt| {
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    rowids.push(row.get::<_, i64>(0)?);
+                }
+                Ok(())
+            });
+        match scanned {
+            Ok(()) => break,
+            Err(err) => {
+                debug_err!("scanning {table} ({order}) stopped: {err}");
+                complete = false;
+            }
+        }
+    }
+    rowids.sort_unstable();
+    rowids.dedup();
+
+    let select = format!("SELECT {column_list} FROM `{table}` WHERE `rowid` = ?1;");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let insert =
+        format!("INSERT OR IGNORE INTO `{table}` ({column_list}) VALUES ({placeholders});");
+
+    let mut copied = 0;
+    for rowid in rowids {
+        let values = damaged.query_row(&select, [rowid], |row| {
+            (0..columns.len())
+                .map(|i| row.get::<_, Value>(i))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        });
+        match values {
+            Ok(values) => copied += fresh.execute(&insert, params_from_iter(values))?,
+            Err(err) => {
+                debug_err!("row {rowid} of {table} is unreadable: {err}");
+                complete = false;
+            }
+        }
+    }
+    Ok((copied, complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::super::path::TestDataDir;
+    use super::super::{insert_reminder, insert_tag, memory_db, schema, set_reminder_tags};
+    use super::*;
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn salvage_drops_links_of_lost_rows_and_keeps_ids_unused() {
+        let damaged = memory_db();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let tag = insert_tag(&damaged, "work", [0, 0, 0], None).unwrap();
+        for note in ["first", "second", "third"] {
+            let id = insert_reminder(&damaged, date, None, note, None, false, &[]).unwrap();
+            set_reminder_tags(&damaged, id, &[tag]).unwrap();
+        }
+        // As if the page holding the newest reminder had been lost.
+        damaged
+            .execute_batch("PRAGMA foreign_keys = OFF; DELETE FROM `Reminder` WHERE `id` = 3;")
+            .unwrap();
+
+        let fresh = Connection::open_in_memory().unwrap();
+        assert_eq!(salvage(&damaged, &fresh).unwrap(), (2, true));
+        assert_eq!(count(&fresh, "SELECT COUNT(*) FROM `ReminderTag`;"), 2);
+        assert_eq!(
+            count(
+                &fresh,
+                "SELECT `seq` FROM `sqlite_sequence` WHERE `name` = 'Reminder';"
+            ),
+            3
+        );
+
+        schema::ensure_schema(&fresh).unwrap();
+        let id = insert_reminder(&fresh, date, None, "fourth", None, false, &[]).unwrap();
+        assert_eq!(id, 4);
+        assert_eq!(
+            count(
+                &fresh,
+                "SELECT COUNT(*) FROM `ReminderTag` WHERE `reminder_id` = 4;"
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn recovers_the_rows_around_a_damaged_page() {
+        let _dir = TestDataDir::new("recover");
+        let db_path = path::db_path();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let page_size: i64 = {
+            let conn = Connection::open(&db_path).unwrap();
+            schema::ensure_schema(&conn).unwrap();
+            let tx = conn.unchecked_transaction().unwrap();
+            for i in 0..400 {
+                let note = format!("reminder {i:03} {}", "x".repeat(200));
+                insert_reminder(&tx, date, None, &note, None, false, &[]).unwrap();
+            }
+            tx.commit().unwrap();
+            conn.pragma_query_value(None, "page_size", |row| row.get(0))
+                .unwrap()
+        };
+
+        // Scribble over the page holding reminder 200.
+        let mut bytes = std::fs::read(&db_path).unwrap();
+        let page_size = page_size as usize;
+        let marker = b"reminder 200 ";
+        let at = bytes
+            .windows(marker.len())
+            .position(|w| w == marker)
+            .unwrap();
+        let page = at / page_size * page_size;
+        assert!(page > 0, "the header page must stay intact");
+        bytes[page..page + page_size].fill(0xA5);
+        std::fs::write(&db_path, &bytes).unwrap();
+
+        let conn = Connection::open(&db_path).unwrap();
+        let err = check_integrity(&conn).unwrap_err();
+        assert!(err.is::<CorruptDatabaseError>(), "{err}");
+        drop(conn);
+
+        let report = recover_db().unwrap();
+        assert!(!report.complete);
+        assert!((300..400).contains(&report.reminders), "{report:?}");
+        assert_eq!(std::fs::read(&report.quarantined).unwrap(), bytes);
+
+        let conn = Connection::open(&db_path).unwrap();
+        check_integrity(&conn).unwrap();
+        let reminders = count(&conn, "SELECT COUNT(*) FROM `Reminder`;");
+        assert_eq!(reminders as usize, report.reminders);
+        // Rows on both sides of the damage were reached.
+        for (id, present) in [(1, true), (201, false), (400, true)] {
+            let found = count(
+                &conn,
+                &format!("SELECT COUNT(*) FROM `Reminder` WHERE `id` = {id};"),
+            );
+            assert_eq!(found == 1, present, "reminder {id}");
+        }
+        schema::ensure_schema(&conn).unwrap();
+    }
+}
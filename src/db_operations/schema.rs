@@ -1,8 +1,115 @@
-use rusqlite::Connection;
+//! Schema migrations, tracked in `PRAGMA user_version`.
+//!
+//! `MIGRATIONS[n - 1]` moves the schema from version `n - 1` to `n`. Each one runs in its own
+//! transaction together with the version bump, so a failed upgrade leaves the database at
+//! the last version that fully applied.
+//!
+//! Databases from before versioning report version 0 but may already have any of the changes
+//! of migrations 1 to 8 (they were applied by probing `PRAGMA table_info`), so those
+//! migrations tolerate finding their change already done. Later ones can rely on the
+//! previous version exactly.
+//!
+//! Before any migration runs, an existing database is backed up (see [`super::backup`]).
+//!
+//! Migrations are only ever appended; a released one is never edited.
 
-pub(super) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+use std::error::Error;
+use std::fmt;
+
+use chrono::Local;
+use rusqlite::{Connection, params};
+
+use super::backup::{self, BackupKind};
+use crate::alerts;
+
+/// SQL expression for a new reminder's `uid`: 32 random hex digits, globally unique enough
+/// to identify the reminder in iCalendar files (see [`crate::ical`]).
+pub(in crate::db_operations) const NEW_UID: &str = "lower(hex(randomblob(16)))";
+
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+const MIGRATIONS: &[Migration] = &[
+    // 1: the 1.0.0 schema
+    create_reminder_table,
+    // 2: time of day
+    |conn| add_column_if_missing(conn, "Reminder", "time", "TEXT NULL"),
+    // 3: repeat rules
+    |conn| add_column_if_missing(conn, "Reminder", "recurrence", "TEXT NULL"),
+    // 4: done reminders
+    |conn| add_column_if_missing(conn, "Reminder", "completed_at", "TEXT NULL"),
+    // 5: overdue re-nag
+    |conn| add_column_if_missing(conn, "Reminder", "overdue_notified_on", "TEXT NULL"),
+    // 6: snooze
+    |conn| add_column_if_missing(conn, "Reminder", "snoozed_until", "TEXT NULL"),
+    // 7: per-reminder alerts
+    replace_notified_level_with_alerts,
+    // 8: iCalendar UIDs
+    add_uid,
+    // 9: tags
+    add_tags,
+    // 10: priority levels
+    add_priority,
+];
+
+/// The schema version this build writes.
+pub(super) const SCHEMA_VERSION: i32 = MIGRATIONS.len() as i32;
+
+/// The database was written by a newer version of the app; opening it could lose data the
+/// newer version relies on.
+#[derive(Debug)]
+pub struct NewerSchemaError {
+    pub found: i32,
+    pub supported: i32,
+}
+
+impl fmt::Display for NewerSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the database has schema version {}, but this version of SilliReminder only \
+             knows up to {}; please update the app",
+            self.found, self.supported
+        )
+    }
+}
+
+impl Error for NewerSchemaError {}
+
+/// Brings the database up to [`SCHEMA_VERSION`].
+pub(super) fn ensure_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
     conn.execute("PRAGMA foreign_keys = ON;", ())?;
 
+    let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(NewerSchemaError {
+            found: version,
+            supported: SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let applied = usize::try_from(version)
+        .map_err(|_| format!("the database has an invalid schema version {version}"))?;
+
+    // An in-memory database (as in the tests) has no file to back up.
+    let on_disk = conn.path().is_some_and(|p| !p.is_empty());
+    if version < SCHEMA_VERSION && on_disk && has_table(conn, "Reminder")? {
+        backup::create_backup(conn, BackupKind::BeforeUpgrade)
+            .map_err(|err| format!("could not back up the database before upgrading: {err}"))?;
+    }
+
+    for (to, migrate) in (version + 1..).zip(&MIGRATIONS[applied..]) {
+        let tx = conn.unchecked_transaction()?;
+        migrate(&tx).map_err(|err| format!("schema migration to version {to} failed: {err}"))?;
+        tx.pragma_update(None, "user_version", to)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Version 1: the schema of 1.0.0.
+fn create_reminder_table(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS `Reminder`(
             `id` INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -12,28 +119,358 @@ pub(super) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
         );",
         (),
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS `idx_reminder_date` ON `Reminder`(`date`);",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Version 7: per-reminder alerts in `ReminderAlert` instead of the single `notified_level`.
+fn replace_notified_level_with_alerts(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(
+        conn,
+        "Reminder",
+        "custom_alerts",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    let seed_alerts = !has_table(conn, "ReminderAlert")?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS `ReminderAlert`(
+            `reminder_id` INTEGER NOT NULL REFERENCES `Reminder`(`id`) ON DELETE CASCADE,
+            `offset_minutes` INTEGER NOT NULL,
+            `fired_at` TEXT NULL,
+            PRIMARY KEY (`reminder_id`, `offset_minutes`)
+        );",
+        (),
+    )?;
+    if seed_alerts {
+        seed_alerts_from_notified_level(conn)?;
+    }
+
+    if has_column(conn, "Reminder", "notified_level")? {
+        conn.execute("ALTER TABLE `Reminder` DROP COLUMN `notified_level`;", ())?;
+    }
+    Ok(())
+}
+
+/// Version 8: a stable `uid` per reminder, for iCalendar export/import.
+fn add_uid(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "Reminder", "uid", "TEXT NULL")?;
+    conn.execute(
+        &format!("UPDATE `Reminder` SET `uid` = {NEW_UID} WHERE `uid` IS NULL;"),
+        (),
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS `idx_reminder_uid` ON `Reminder`(`uid`);",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Version 9: tags, with an optional color and lead times of their own, many per reminder.
+fn add_tags(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE `Tag`(
+            `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+            `name` TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            `color` TEXT NOT NULL,
+            `alert_offsets` TEXT NULL
+        );",
+        (),
+    )?;
+    conn.execute(
+        "CREATE TABLE `ReminderTag`(
+            `reminder_id` INTEGER NOT NULL REFERENCES `Reminder`(`id`) ON DELETE CASCADE,
+            `tag_id` INTEGER NOT NULL REFERENCES `Tag`(`id`) ON DELETE CASCADE,
+            PRIMARY KEY (`reminder_id`, `tag_id`)
+        );",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX `idx_reminder_tag_tag` ON `ReminderTag`(`tag_id`);",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Version 10: a priority per reminder (see [`super::Priority`]); existing ones are normal.
+fn add_priority(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "ALTER TABLE `Reminder` ADD COLUMN `priority` INTEGER NOT NULL DEFAULT 1;",
+        (),
+    )?;
+    Ok(())
+}
+
+fn has_table(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM `sqlite_master` WHERE `type` = 'table' AND `name` = ?1;",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+}
+
+/// Gives every active reminder the default alerts, carrying over what the old single
+/// `notified_level` (0..=4: nothing, <= 7 days, <= 3 days, <= 1 day, at time) already announced.
+fn seed_alerts_from_notified_level(conn: &Connection) -> rusqlite::Result<()> {
+    let has_level = has_column(conn, "Reminder", "notified_level")?;
+    let level_column = if has_level { "`notified_level`" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT `id`, `time` IS NOT NULL, {level_column}
+         FROM `Reminder`
+         WHERE `completed_at` IS NULL;"
+    ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, bool>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let now = Local::now()
+        .naive_local()
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    for (id, timed, level) in rows {
+        // Smallest offset the old level had already announced.
+        let announced_down_to = match level {
+            ..=0 => None,
+            1 => Some(7 * alerts::DAY),
+            2 => Some(3 * alerts::DAY),
+            3 => Some(alerts::DAY),
+            _ => Some(0),
+        };
+
+        for offset in alerts::effective_offsets(&alerts::DEFAULT_OFFSETS, timed) {
+            let fired = announced_down_to.is_some_and(|min| offset >= min);
+            conn.execute(
+                "INSERT OR IGNORE INTO `ReminderAlert` (`reminder_id`, `offset_minutes`, `fired_at`)
+                 VALUES (?1, ?2, ?3);",
+                params![id, offset, fired.then_some(now.as_str())],
+            )?;
+        }
+    }
+
+    Ok(())
+}
 
-    // Migration for older DBs.
-    let mut stmt = conn.prepare("PRAGMA table_info(`Reminder`);")?;
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(`{table}`);"))?;
     let cols = stmt.query_map([], |row| row.get::<_, String>(1))?;
-    let mut has_notified_level = false;
     for c in cols {
-        if c? == "notified_level" {
-            has_notified_level = true;
-            break;
+        if c? == column {
+            return Ok(true);
         }
     }
-    if !has_notified_level {
+    Ok(false)
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !has_column(conn, table, column)? {
         conn.execute(
-            "ALTER TABLE `Reminder` ADD COLUMN `notified_level` INTEGER NOT NULL DEFAULT 0;",
+            &format!("ALTER TABLE `{table}` ADD COLUMN `{column}` {definition};"),
             (),
         )?;
     }
+    Ok(())
+}
 
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS `idx_reminder_date` ON `Reminder`(`date`);",
-        (),
-    )?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_operations::{Priority, list_reminders};
 
-    Ok(())
+    const REMINDER_COLUMNS: [&str; 11] = [
+        "id",
+        "date",
+        "note",
+        "time",
+        "recurrence",
+        "completed_at",
+        "overdue_notified_on",
+        "snoozed_until",
+        "custom_alerts",
+        "uid",
+        "priority",
+    ];
+
+    /// The default alerts of an untimed reminder: 7, 3 and 1 day before.
+    const DEFAULT_UNTIMED: [i64; 3] = [7 * alerts::DAY, 3 * alerts::DAY, alerts::DAY];
+
+    fn user_version(conn: &Connection) -> i32 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    /// The 1.0.0 layout (before versioning), optionally still without `notified_level`,
+    /// holding one reminder per old notification level (0..=4).
+    fn layout_1_0_0(notified_level: bool) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        let level_column = if notified_level {
+            ", `notified_level` INTEGER NOT NULL DEFAULT 0"
+        } else {
+            ""
+        };
+        conn.execute_batch(&format!(
+            "CREATE TABLE `Reminder`(
+                `id` INTEGER PRIMARY KEY AUTOINCREMENT,
+                `date` TEXT NOT NULL,
+                `note` TEXT NOT NULL{level_column}
+            );"
+        ))
+        .unwrap();
+        for level in 0..=4 {
+            if notified_level {
+                conn.execute(
+                    "INSERT INTO `Reminder` (`date`, `note`, `notified_level`)
+                     VALUES ('2030-01-10', 'old', ?1);",
+                    [level],
+                )
+                .unwrap();
+            } else {
+                conn.execute(
+                    "INSERT INTO `Reminder` (`date`, `note`) VALUES ('2030-01-10', 'old');",
+                    (),
+                )
+                .unwrap();
+            }
+        }
+        conn
+    }
+
+    /// A database with the changes of the first `applied` migrations, reporting
+    /// `user_version` (0 for the layouts from before versioning, whose changes were
+    /// applied by probing). It holds one untimed reminder, with a pending 1-day alert once
+    /// alerts exist.
+    fn layout(applied: usize, user_version: i32) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        for migrate in &MIGRATIONS[..applied] {
+            migrate(&conn).unwrap();
+        }
+        conn.pragma_update(None, "user_version", user_version)
+            .unwrap();
+
+        conn.execute(
+            "INSERT INTO `Reminder` (`date`, `note`) VALUES ('2030-01-10', 'old');",
+            (),
+        )
+        .unwrap();
+        // From version 8 on, every reminder was written with a uid.
+        if has_column(&conn, "Reminder", "uid").unwrap() {
+            conn.execute(&format!("UPDATE `Reminder` SET `uid` = {NEW_UID};"), ())
+                .unwrap();
+        }
+        if has_table(&conn, "ReminderAlert").unwrap() {
+            conn.execute(
+                "INSERT INTO `ReminderAlert` (`reminder_id`, `offset_minutes`) VALUES (1, ?1);",
+                [alerts::DAY],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    /// Upgrades `conn` and checks it has the current schema; returns the alerts of its
+    /// reminders as (offset, fired) pairs.
+    fn upgrade(conn: &Connection) -> Vec<Vec<(i64, bool)>> {
+        ensure_schema(conn).unwrap();
+        assert_eq!(user_version(conn), SCHEMA_VERSION);
+        for column in REMINDER_COLUMNS {
+            assert!(has_column(conn, "Reminder", column).unwrap(), "no {column}");
+        }
+        assert!(!has_column(conn, "Reminder", "notified_level").unwrap());
+        for table in ["ReminderAlert", "Tag", "ReminderTag"] {
+            assert!(has_table(conn, table).unwrap(), "no {table}");
+        }
+
+        // Upgrading again is a no-op.
+        ensure_schema(conn).unwrap();
+        assert_eq!(user_version(conn), SCHEMA_VERSION);
+
+        let reminders = list_reminders(conn).unwrap();
+        assert!(!reminders.is_empty());
+        reminders
+            .iter()
+            .map(|r| {
+                assert!(r.uid.is_some());
+                assert_eq!(r.priority, Priority::Normal);
+                r.alerts
+                    .iter()
+                    .map(|a| (a.offset_minutes, a.fired_at.is_some()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn untimed_defaults(fired: usize) -> Vec<(i64, bool)> {
+        DEFAULT_UNTIMED
+            .iter()
+            .enumerate()
+            .map(|(i, &offset)| (offset, i < fired))
+            .collect()
+    }
+
+    #[test]
+    fn upgrades_1_0_0_with_notified_level() {
+        let alerts = upgrade(&layout_1_0_0(true));
+        // Levels 1..=3 had announced 7, 3 and 1 day; level 4 (at time) all of them.
+        let expected: Vec<_> = [0, 1, 2, 3, 3].into_iter().map(untimed_defaults).collect();
+        assert_eq!(alerts, expected);
+    }
+
+    #[test]
+    fn upgrades_1_0_0_without_notified_level() {
+        let alerts = upgrade(&layout_1_0_0(false));
+        assert_eq!(alerts, vec![untimed_defaults(0); 5]);
+    }
+
+    #[test]
+    fn upgrades_pre_versioned_probed_layouts() {
+        for applied in 1..=8 {
+            let alerts = upgrade(&layout(applied, 0));
+            let expected = if applied < 7 {
+                untimed_defaults(0)
+            } else {
+                vec![(alerts::DAY, false)]
+            };
+            assert_eq!(alerts, vec![expected], "layout after migration {applied}");
+        }
+    }
+
+    #[test]
+    fn upgrades_every_version() {
+        for version in 1..SCHEMA_VERSION {
+            let alerts = upgrade(&layout(version as usize, version));
+            let expected = if version < 7 {
+                untimed_defaults(0)
+            } else {
+                vec![(alerts::DAY, false)]
+            };
+            assert_eq!(alerts, vec![expected], "version {version}");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let newer = layout(MIGRATIONS.len(), SCHEMA_VERSION + 1);
+        let err = ensure_schema(&newer).unwrap_err();
+        assert!(err.is::<NewerSchemaError>());
+
+        let negative = layout(MIGRATIONS.len(), -1);
+        assert!(ensure_schema(&negative).is_err());
+        assert_eq!(user_version(&negative), -1);
+    }
 }
@@ -1,12 +1,118 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::recurrence::Recurrence;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Reminder {
     pub id: i64,
     pub date: NaiveDate,
+    /// Optional time of day; `None` means "any time that day".
+    pub time: Option<NaiveTime>,
     pub note: String,
-    pub notified_level: u8,
+    /// Repeat rule; the row holds the *current* occurrence and is rolled forward.
+    pub recurrence: Option<Recurrence>,
+    /// When the reminder was marked as done; `None` while it is still active.
+    pub completed_at: Option<NaiveDateTime>,
+    /// Last day an "overdue" notification was sent for this reminder.
+    pub overdue_notified_on: Option<NaiveDate>,
+    /// Notifications are held back until this moment, then re-fired once.
+    pub snoozed_until: Option<NaiveDateTime>,
+    /// `true` when `alerts` were picked for this reminder; otherwise they follow the global
+    /// default lead times.
+    pub custom_alerts: bool,
+    /// Alerts ordered from the earliest (largest offset) to the latest.
+    pub alerts: Vec<Alert>,
+    /// Stable identifier across exports and imports (iCalendar `UID`).
+    pub uid: Option<String>,
+    /// Ordered by name.
+    pub tags: Vec<Tag>,
+    pub priority: Priority,
+}
+
+/// How much a reminder matters: it sorts before others due the same day, and sets how loud
+/// its notifications are (see [`crate::sinks`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl Priority {
+    pub const ALL: [Self; 4] = [Self::Low, Self::Normal, Self::High, Self::Critical];
+
+    /// The name used in CSV/JSON files and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Colors new tags get, in turn; each can be changed afterwards.
+pub const TAG_COLORS: [[u8; 3]; 8] = [
+    [59, 130, 246],
+    [34, 197, 94],
+    [249, 115, 22],
+    [168, 85, 247],
+    [239, 68, 68],
+    [20, 184, 166],
+    [236, 72, 153],
+    [107, 114, 128],
+];
+
+/// A label such as "work" or "finance", shown as a colored chip on its reminders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    /// sRGB.
+    pub color: [u8; 3],
+    /// Lead times for its reminders that have no alerts of their own, instead of the global
+    /// defaults (see [`crate::alerts`]).
+    pub alert_offsets: Option<Vec<i64>>,
+}
+
+/// One notification of a reminder, `offset_minutes` before it is due.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub offset_minutes: i64,
+    /// When this alert went out (or was skipped as stale); `None` while still pending.
+    pub fired_at: Option<NaiveDateTime>,
+}
+
+impl Reminder {
+    /// The moment the reminder is due: `date` at `time`, or the start of `date` when untimed.
+    pub fn due_at(&self) -> NaiveDateTime {
+        self.date.and_time(self.time.unwrap_or(NaiveTime::MIN))
+    }
+
+    /// Overdue once its whole day has passed (timed reminders are "due now" until then).
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.date < today
+    }
+
+    /// Full days since the due date; only meaningful when [`Reminder::is_overdue`].
+    pub fn days_late(&self, today: NaiveDate) -> i64 {
+        (today - self.date).num_days()
+    }
+
+    /// The moment `alert` is due to go out.
+    pub fn alert_at(&self, alert: &Alert) -> NaiveDateTime {
+        self.due_at() - chrono::Duration::minutes(alert.offset_minutes)
+    }
 }
 
 pub(in crate::db_operations) fn parse_db_date(date_str: &str) -> rusqlite::Result<NaiveDate> {
@@ -14,3 +120,127 @@ pub(in crate::db_operations) fn parse_db_date(date_str: &str) -> rusqlite::Resul
         rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
     })
 }
+
+pub(in crate::db_operations) fn parse_db_optional_date(
+    date_str: Option<String>,
+) -> rusqlite::Result<Option<NaiveDate>> {
+    date_str.as_deref().map(parse_db_date).transpose()
+}
+
+pub(in crate::db_operations) fn parse_db_time(
+    time_str: Option<String>,
+) -> rusqlite::Result<Option<NaiveTime>> {
+    let Some(time_str) = time_str else {
+        return Ok(None);
+    };
+
+    NaiveTime::parse_from_str(&time_str, "%H:%M")
+        .map(Some)
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+pub(in crate::db_operations) fn format_db_time(time: Option<NaiveTime>) -> Option<String> {
+    time.map(|t| t.format("%H:%M").to_string())
+}
+
+pub(in crate::db_operations) fn parse_db_recurrence(
+    rule: Option<String>,
+) -> rusqlite::Result<Option<Recurrence>> {
+    let Some(rule) = rule else {
+        return Ok(None);
+    };
+
+    rule.parse::<Recurrence>().map(Some).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+pub(in crate::db_operations) fn parse_db_datetime(
+    datetime_str: Option<String>,
+) -> rusqlite::Result<Option<NaiveDateTime>> {
+    let Some(datetime_str) = datetime_str else {
+        return Ok(None);
+    };
+
+    NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S")
+        .map(Some)
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+pub(in crate::db_operations) fn format_db_datetime(
+    datetime: Option<NaiveDateTime>,
+) -> Option<String> {
+    datetime.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// A priority as stored: 0 (low) to 3 (critical).
+pub(in crate::db_operations) fn format_db_priority(priority: Priority) -> i64 {
+    priority as i64
+}
+
+pub(in crate::db_operations) fn parse_db_priority(value: i64) -> rusqlite::Result<Priority> {
+    usize::try_from(value)
+        .ok()
+        .and_then(|i| Priority::ALL.get(i).copied())
+        .ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Integer,
+                format!("invalid priority {value}").into(),
+            )
+        })
+}
+
+/// A tag color as stored: `#rrggbb`.
+pub(in crate::db_operations) fn format_db_color(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+pub(in crate::db_operations) fn parse_db_color(color_str: &str) -> rusqlite::Result<[u8; 3]> {
+    let invalid = || {
+        rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            format!("invalid color {color_str:?}").into(),
+        )
+    };
+
+    let hex = color_str
+        .strip_prefix('#')
+        .filter(|h| h.len() == 6)
+        .ok_or_else(invalid)?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Lead times as stored: minutes, comma-separated.
+pub(in crate::db_operations) fn format_db_offsets(offsets: Option<&[i64]>) -> Option<String> {
+    offsets.map(|o| o.iter().map(i64::to_string).collect::<Vec<_>>().join(","))
+}
+
+pub(in crate::db_operations) fn parse_db_offsets(
+    offsets_str: Option<String>,
+) -> rusqlite::Result<Option<Vec<i64>>> {
+    let Some(offsets_str) = offsets_str else {
+        return Ok(None);
+    };
+
+    offsets_str
+        .split(',')
+        .filter(|o| !o.is_empty())
+        .map(|o| {
+            o.trim().parse::<i64>().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })
+        })
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map(Some)
+}
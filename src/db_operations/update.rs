@@ -1,16 +1,292 @@
 use std::error::Error;
 
+use chrono::{NaiveDate, NaiveDateTime};
 use rusqlite::{Connection, params};
 
-pub fn set_reminder_notified_level(
+use super::{
+    Alert, Priority, Reminder, Tag, format_db_color, format_db_datetime, format_db_offsets,
+    format_db_priority, format_db_time, insert_completed_copy,
+};
+use crate::alerts;
+use crate::recurrence::Recurrence;
+
+/// Records that the alert `offset_minutes` before reminder `id` went out (`None` re-arms it).
+pub fn set_alert_fired(
+    conn: &Connection,
+    id: i64,
+    offset_minutes: i64,
+    fired_at: Option<NaiveDateTime>,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `ReminderAlert` SET `fired_at` = ?1
+         WHERE `reminder_id` = ?2 AND `offset_minutes` = ?3;",
+        params![format_db_datetime(fired_at), id, offset_minutes],
+    )?;
+    Ok(())
+}
+
+/// Replaces the alerts of reminder `id`. `custom` tells whether they were picked for this
+/// reminder or follow the global defaults.
+pub fn set_reminder_alerts(
+    conn: &Connection,
+    id: i64,
+    custom: bool,
+    alerts: &[Alert],
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder` SET `custom_alerts` = ?1 WHERE `id` = ?2;",
+        params![custom, id],
+    )?;
+    replace_alerts(conn, id, alerts)
+}
+
+fn replace_alerts(conn: &Connection, id: i64, alerts: &[Alert]) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "DELETE FROM `ReminderAlert` WHERE `reminder_id` = ?1;",
+        params![id],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "INSERT OR REPLACE INTO `ReminderAlert` (`reminder_id`, `offset_minutes`, `fired_at`)
+         VALUES (?1, ?2, ?3);",
+    )?;
+    for alert in alerts {
+        stmt.execute(params![
+            id,
+            alert.offset_minutes,
+            format_db_datetime(alert.fired_at)
+        ])?;
+    }
+    Ok(())
+}
+
+/// Marks `r` as done at `now`. A recurring reminder instead archives the finished
+/// occurrence as a completed copy and moves on to its next occurrence.
+pub fn complete_reminder(
+    conn: &Connection,
+    r: &Reminder,
+    now: NaiveDateTime,
+) -> Result<(), Box<dyn Error>> {
+    let next = r
+        .recurrence
+        .as_ref()
+        .and_then(|rule| rule.advance(r.date, r.date));
+    let Some((next_date, next_rule)) = next else {
+        return set_reminder_completed(conn, r.id, Some(now));
+    };
+
+    let mut next_occurrence = r.clone();
+    next_occurrence.date = next_date;
+    let alerts = alerts::fresh_occurrence_alerts(now, &next_occurrence);
+
+    let tx = conn.unchecked_transaction()?;
+    insert_completed_copy(&tx, r, now)?;
+    roll_reminder_forward(&tx, r.id, next_date, &next_rule, &alerts)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Moves a recurring reminder to its next occurrence.
+///
+/// `recurrence` is the rule to store alongside it (its `COUNT` may have been decremented)
+/// and `alerts` the fresh alert state for the new occurrence.
+pub fn roll_reminder_forward(
+    conn: &Connection,
+    id: i64,
+    date: NaiveDate,
+    recurrence: &Recurrence,
+    alerts: &[Alert],
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder`
+         SET `date` = ?1, `recurrence` = ?2,
+             `overdue_notified_on` = NULL, `snoozed_until` = NULL
+         WHERE `id` = ?3;",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            recurrence.to_string(),
+            id
+        ],
+    )?;
+    replace_alerts(conn, id, alerts)
+}
+
+/// Overwrites the editable fields of a reminder (date, time, note, recurrence, priority,
+/// alerts, tags) together with its notification state, which the caller recomputes when the
+/// date moves. All of it is written in one transaction.
+pub fn update_reminder(conn: &Connection, reminder: &Reminder) -> Result<(), Box<dyn Error>> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE `Reminder`
+         SET `date` = ?1, `time` = ?2, `note` = ?3, `recurrence` = ?4, `custom_alerts` = ?5,
+             `overdue_notified_on` = ?6, `snoozed_until` = ?7, `priority` = ?8
+         WHERE `id` = ?9;",
+        params![
+            reminder.date.format("%Y-%m-%d").to_string(),
+            format_db_time(reminder.time),
+            reminder.note,
+            reminder.recurrence.as_ref().map(|r| r.to_string()),
+            reminder.custom_alerts,
+            reminder
+                .overdue_notified_on
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+            format_db_datetime(reminder.snoozed_until),
+            format_db_priority(reminder.priority),
+            reminder.id
+        ],
+    )?;
+    let tag_ids: Vec<i64> = reminder.tags.iter().map(|t| t.id).collect();
+    set_reminder_tags(&tx, reminder.id, &tag_ids)?;
+    replace_alerts(&tx, reminder.id, &reminder.alerts)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Changes the priority of reminder `id`.
+pub fn set_reminder_priority(
+    conn: &Connection,
+    id: i64,
+    priority: Priority,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder` SET `priority` = ?1 WHERE `id` = ?2;",
+        params![format_db_priority(priority), id],
+    )?;
+    Ok(())
+}
+
+/// Replaces the tags of reminder `id`.
+pub fn set_reminder_tags(
+    conn: &Connection,
+    id: i64,
+    tag_ids: &[i64],
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "DELETE FROM `ReminderTag` WHERE `reminder_id` = ?1;",
+        params![id],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO `ReminderTag` (`reminder_id`, `tag_id`) VALUES (?1, ?2);",
+    )?;
+    for tag_id in tag_ids {
+        stmt.execute(params![id, tag_id])?;
+    }
+    Ok(())
+}
+
+/// Saves a tag's name, color and lead times.
+pub fn update_tag(conn: &Connection, tag: &Tag) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Tag` SET `name` = ?1, `color` = ?2, `alert_offsets` = ?3 WHERE `id` = ?4;",
+        params![
+            tag.name,
+            format_db_color(tag.color),
+            format_db_offsets(tag.alert_offsets.as_deref()),
+            tag.id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Marks a reminder as done (`Some`) or restores it to the active list (`None`).
+pub fn set_reminder_completed(
+    conn: &Connection,
+    id: i64,
+    completed_at: Option<NaiveDateTime>,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder` SET `completed_at` = ?1 WHERE `id` = ?2;",
+        params![format_db_datetime(completed_at), id],
+    )?;
+    Ok(())
+}
+
+pub fn set_reminder_overdue_notified(
     conn: &Connection,
     id: i64,
-    notified_level: u8,
+    day: NaiveDate,
 ) -> Result<(), Box<dyn Error>> {
-    let notified_level: i64 = (notified_level.min(3)) as i64;
     conn.execute(
-        "UPDATE `Reminder` SET `notified_level` = ?1 WHERE `id` = ?2;",
-        params![notified_level, id],
+        "UPDATE `Reminder` SET `overdue_notified_on` = ?1 WHERE `id` = ?2;",
+        params![day.format("%Y-%m-%d").to_string(), id],
+    )?;
+    Ok(())
+}
+
+/// Replaces a reminder's `uid`, e.g. with the one of the calendar entry it was imported from.
+pub fn set_reminder_uid(conn: &Connection, id: i64, uid: &str) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder` SET `uid` = ?1 WHERE `id` = ?2;",
+        params![uid, id],
     )?;
     Ok(())
 }
+
+/// Holds back notifications for a reminder until `until` (`None` cancels the snooze).
+pub fn set_reminder_snoozed(
+    conn: &Connection,
+    id: i64,
+    until: Option<NaiveDateTime>,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE `Reminder` SET `snoozed_until` = ?1 WHERE `id` = ?2;",
+        params![format_db_datetime(until), id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::super::{get_reminder, insert_reminder, insert_tag, list_completed_reminders};
+    use super::*;
+
+    #[test]
+    fn completed_occurrences_keep_their_alerts_when_restored() {
+        let conn = super::super::memory_db();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let rule: Recurrence = "FREQ=WEEKLY".parse().unwrap();
+        let alerts: Vec<Alert> = [alerts::DAY, 0]
+            .into_iter()
+            .map(|offset_minutes| Alert {
+                offset_minutes,
+                fired_at: None,
+            })
+            .collect();
+        let id = insert_reminder(
+            &conn,
+            date,
+            NaiveTime::from_hms_opt(9, 0, 0),
+            "Stand-up notes",
+            Some(&rule),
+            true,
+            &alerts,
+        )
+        .unwrap();
+        let tag = insert_tag(&conn, "work", [0, 0, 0], None).unwrap();
+        set_reminder_tags(&conn, id, &[tag]).unwrap();
+
+        let series = get_reminder(&conn, id).unwrap().unwrap();
+        let now = date.and_hms_opt(9, 5, 0).unwrap();
+        complete_reminder(&conn, &series, now).unwrap();
+
+        let series = get_reminder(&conn, id).unwrap().unwrap();
+        assert_eq!(series.date, NaiveDate::from_ymd_opt(2026, 10, 21).unwrap());
+        let [copy] = list_completed_reminders(&conn).unwrap().try_into().unwrap();
+        assert_eq!(
+            (copy.date, copy.recurrence, copy.completed_at),
+            (date, None, Some(now))
+        );
+
+        set_reminder_completed(&conn, copy.id, None).unwrap();
+        let restored = get_reminder(&conn, copy.id).unwrap().unwrap();
+        assert_eq!(restored.alerts, alerts);
+        assert!(restored.custom_alerts);
+        assert_eq!(
+            restored.tags.iter().map(|t| t.id).collect::<Vec<_>>(),
+            [tag]
+        );
+    }
+}
@@ -1,29 +1,85 @@
 #![allow(dead_code)]
 
-use std::sync::OnceLock;
+//! UI strings. The text lives in per-language catalogs (`locales/*.toml`, see [`catalog`]);
+//! the functions here give each message a typed signature so call sites stay simple.
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::db_operations::{BackupKind, Priority};
+use crate::recurrence::{Frequency, Recurrence};
+use crate::settings::Theme;
+
+mod catalog;
+#[cfg(test)]
+mod tests;
+
+use catalog::{format, plural, text};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Language {
     Pl,
     En,
+    De,
+    Uk,
 }
 
-static LANG: OnceLock<Language> = OnceLock::new();
+impl Language {
+    pub const ALL: [Language; 4] = [Language::Pl, Language::En, Language::De, Language::Uk];
 
-pub fn init() {
-    let _ = LANG.set(detect_language());
+    /// The language's own name, as shown in the language picker.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::Pl => "Polski",
+            Language::En => "English",
+            Language::De => "Deutsch",
+            Language::Uk => "Українська",
+        }
+    }
+}
+
+/// Current UI language, shared with the tray thread; see [`set_language`].
+static LANG: AtomicU8 = AtomicU8::new(UNSET);
+const UNSET: u8 = u8::MAX;
+
+/// Picks the UI language: `preferred` (from settings) or the system locale.
+pub fn init(preferred: Option<Language>) {
+    set_language(preferred);
+}
+
+/// Switches the UI language at runtime; `None` goes back to the system locale. Widgets and
+/// the tray read [`language`] when drawing, so the change shows up on the next frame.
+pub fn set_language(preferred: Option<Language>) {
+    let lang = preferred.unwrap_or_else(detect_language);
+    LANG.store(lang as u8, Ordering::Relaxed);
 }
 
 pub fn language() -> Language {
-    *LANG.get_or_init(detect_language)
+    match LANG.load(Ordering::Relaxed) {
+        UNSET => {
+            let lang = detect_language();
+            LANG.store(lang as u8, Ordering::Relaxed);
+            lang
+        }
+        n => Language::ALL
+            .into_iter()
+            .find(|l| *l as u8 == n)
+            .unwrap_or(Language::En),
+    }
 }
 
-fn detect_language() -> Language {
+pub fn detect_language() -> Language {
     let locale = sys_locale::get_locale().unwrap_or_default();
     let locale = locale.to_ascii_lowercase();
 
     if locale.starts_with("pl") {
         Language::Pl
+    } else if locale.starts_with("de") {
+        Language::De
+    } else if locale.starts_with("uk") {
+        Language::Uk
     } else {
         Language::En
     }
@@ -38,66 +94,457 @@ pub fn app_header(_lang: Language) -> &'static str {
 }
 
 pub fn ui_settings(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Ustawienia",
-        Language::En => "Settings",
-    }
+    text(lang, "ui_settings")
 }
 
 pub fn ui_start_with_system(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Włącz podczas włączania systemu",
-        Language::En => "Start with system",
+    text(lang, "ui_start_with_system")
+}
+
+pub fn ui_autostart_failed(lang: Language) -> &'static str {
+    text(lang, "ui_autostart_failed")
+}
+
+pub fn ui_autostart_missing(lang: Language) -> &'static str {
+    text(lang, "ui_autostart_missing")
+}
+
+pub fn ui_autostart_unexpected(lang: Language) -> &'static str {
+    text(lang, "ui_autostart_unexpected")
+}
+
+pub fn ui_autostart_elsewhere(lang: Language, path: &str) -> String {
+    format(text(lang, "ui_autostart_elsewhere"), &[("path", path)])
+}
+
+pub fn ui_autostart_repair(lang: Language) -> &'static str {
+    text(lang, "ui_autostart_repair")
+}
+
+pub fn ui_autostart_ignore(lang: Language) -> &'static str {
+    text(lang, "ui_autostart_ignore")
+}
+
+pub fn ui_language(lang: Language) -> &'static str {
+    text(lang, "ui_language")
+}
+
+pub fn ui_language_system(lang: Language) -> &'static str {
+    text(lang, "ui_language_system")
+}
+
+pub fn ui_theme(lang: Language) -> &'static str {
+    text(lang, "ui_theme")
+}
+
+pub fn theme_name(lang: Language, theme: Theme) -> &'static str {
+    match theme {
+        Theme::System => text(lang, "theme_system"),
+        Theme::Light => text(lang, "theme_light"),
+        Theme::Dark => text(lang, "theme_dark"),
     }
 }
 
-pub fn ui_add(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Dodaj",
-        Language::En => "Add",
+pub fn ui_overdue_renag(lang: Language) -> &'static str {
+    text(lang, "ui_overdue_renag")
+}
+
+pub fn ui_notify_by(lang: Language) -> &'static str {
+    text(lang, "ui_notify_by")
+}
+
+pub fn sink_tray_balloon(lang: Language) -> &'static str {
+    text(lang, "sink_tray_balloon")
+}
+
+pub fn sink_message_box(lang: Language) -> &'static str {
+    text(lang, "sink_message_box")
+}
+
+pub fn sink_desktop(lang: Language) -> &'static str {
+    text(lang, "sink_desktop")
+}
+
+pub fn sink_log_file(lang: Language) -> &'static str {
+    text(lang, "sink_log_file")
+}
+
+pub fn ui_default_alerts(lang: Language) -> &'static str {
+    text(lang, "ui_default_alerts")
+}
+
+pub fn ui_custom_alerts(lang: Language) -> &'static str {
+    text(lang, "ui_custom_alerts")
+}
+
+pub fn alerts_hint(lang: Language) -> &'static str {
+    text(lang, "alerts_hint")
+}
+
+pub fn ui_tags(lang: Language) -> &'static str {
+    text(lang, "ui_tags")
+}
+
+pub fn ui_new_tag_hint(lang: Language) -> &'static str {
+    text(lang, "ui_new_tag_hint")
+}
+
+pub fn ui_tag_exists(lang: Language) -> &'static str {
+    text(lang, "ui_tag_exists")
+}
+
+pub fn ui_tag_save_failed(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_tag_save_failed"), &[("error", error)])
+}
+
+pub fn ui_delete_tag(lang: Language) -> &'static str {
+    text(lang, "ui_delete_tag")
+}
+
+pub fn ui_filter_all(lang: Language) -> &'static str {
+    text(lang, "ui_filter_all")
+}
+
+pub fn ui_priority(lang: Language) -> &'static str {
+    text(lang, "ui_priority")
+}
+
+pub fn priority_name(lang: Language, priority: Priority) -> &'static str {
+    match priority {
+        Priority::Low => text(lang, "priority_low"),
+        Priority::Normal => text(lang, "priority_normal"),
+        Priority::High => text(lang, "priority_high"),
+        Priority::Critical => text(lang, "priority_critical"),
     }
 }
 
-pub fn ui_add_button(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Dodaj",
-        Language::En => "Add",
+pub fn ui_data(lang: Language) -> &'static str {
+    text(lang, "ui_data")
+}
+
+pub fn ui_export(lang: Language) -> &'static str {
+    text(lang, "ui_export")
+}
+
+pub fn ui_import(lang: Language) -> &'static str {
+    text(lang, "ui_import")
+}
+
+pub fn ui_exported(lang: Language, count: usize, path: &str) -> String {
+    format(
+        &plural(lang, "ui_exported", count as i64),
+        &[("path", path)],
+    )
+}
+
+pub fn ui_export_failed(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_export_failed"), &[("error", error)])
+}
+
+pub fn ui_import_failed(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_import_failed"), &[("error", error)])
+}
+
+pub fn ui_import_ready(lang: Language, count: usize) -> String {
+    plural(lang, "ui_import_ready", count as i64)
+}
+
+pub fn ui_import_skipped(lang: Language, duplicates: usize, past: usize, invalid: usize) -> String {
+    format(
+        text(lang, "ui_import_skipped"),
+        &[
+            ("duplicates", &duplicates.to_string()),
+            ("past", &past.to_string()),
+            ("invalid", &invalid.to_string()),
+        ],
+    )
+}
+
+pub fn ui_import_unsupported_rule(lang: Language, rule: &str) -> String {
+    format(text(lang, "ui_import_unsupported_rule"), &[("rule", rule)])
+}
+
+pub fn ui_import_more_errors(lang: Language, count: usize) -> String {
+    plural(lang, "ui_import_more_errors", count as i64)
+}
+
+pub fn ui_import_confirm(lang: Language) -> &'static str {
+    text(lang, "ui_import_confirm")
+}
+
+pub fn ui_imported(lang: Language, count: usize) -> String {
+    plural(lang, "ui_imported", count as i64)
+}
+
+pub fn ui_backups_keep(lang: Language) -> &'static str {
+    text(lang, "ui_backups_keep")
+}
+
+pub fn ui_restore_backup(lang: Language) -> &'static str {
+    text(lang, "ui_restore_backup")
+}
+
+pub fn ui_no_backups(lang: Language) -> &'static str {
+    text(lang, "ui_no_backups")
+}
+
+pub fn ui_backup_entry(lang: Language, date: &str, kind: &str, count: i64) -> String {
+    format(
+        &plural(lang, "ui_backup_entry", count),
+        &[("date", date), ("kind", kind)],
+    )
+}
+
+pub fn ui_backup_unreadable(lang: Language, date: &str, kind: &str) -> String {
+    format(
+        text(lang, "ui_backup_unreadable"),
+        &[("date", date), ("kind", kind)],
+    )
+}
+
+pub fn ui_backup_too_new(lang: Language) -> &'static str {
+    text(lang, "ui_backup_too_new")
+}
+
+pub fn backup_kind_name(lang: Language, kind: BackupKind) -> &'static str {
+    match kind {
+        BackupKind::Startup => text(lang, "backup_kind_startup"),
+        BackupKind::BeforeUpgrade => text(lang, "backup_kind_upgrade"),
+        BackupKind::BeforeRestore => text(lang, "backup_kind_restore"),
     }
 }
 
+pub fn ui_restored(lang: Language, date: &str) -> String {
+    format(text(lang, "ui_restored"), &[("date", date)])
+}
+
+pub fn ui_restore_failed(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_restore_failed"), &[("error", error)])
+}
+
+pub fn ui_add(lang: Language) -> &'static str {
+    text(lang, "ui_add")
+}
+
+pub fn ui_add_button(lang: Language) -> &'static str {
+    text(lang, "ui_add_button")
+}
+
 pub fn ui_note_hint(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Notatka...",
-        Language::En => "Note...",
+    text(lang, "ui_note_hint")
+}
+
+pub fn ui_quick_add_hint(lang: Language) -> &'static str {
+    text(lang, "ui_quick_add_hint")
+}
+
+pub fn ui_quick_add_no_date(lang: Language) -> &'static str {
+    text(lang, "ui_quick_add_no_date")
+}
+
+pub fn ui_quick_add_no_note(lang: Language) -> &'static str {
+    text(lang, "ui_quick_add_no_note")
+}
+
+pub fn ui_time_toggle(lang: Language) -> &'static str {
+    text(lang, "ui_time_toggle")
+}
+
+pub fn recurrence_frequency(lang: Language, frequency: Option<Frequency>) -> &'static str {
+    match frequency {
+        None => text(lang, "frequency_none"),
+        Some(Frequency::Daily) => text(lang, "frequency_daily"),
+        Some(Frequency::Weekly) => text(lang, "frequency_weekly"),
+        Some(Frequency::Monthly) => text(lang, "frequency_monthly"),
+        Some(Frequency::Yearly) => text(lang, "frequency_yearly"),
     }
 }
 
-pub fn ui_planned(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Zaplanowane",
-        Language::En => "Planned",
+pub fn recurrence_every(lang: Language) -> &'static str {
+    text(lang, "recurrence_every")
+}
+
+pub fn recurrence_last_day(lang: Language) -> &'static str {
+    text(lang, "recurrence_last_day")
+}
+
+pub fn recurrence_ends(lang: Language) -> &'static str {
+    text(lang, "recurrence_ends")
+}
+
+pub fn recurrence_end_never(lang: Language) -> &'static str {
+    text(lang, "recurrence_end_never")
+}
+
+pub fn recurrence_end_until(lang: Language) -> &'static str {
+    text(lang, "recurrence_end_until")
+}
+
+pub fn recurrence_end_count(lang: Language) -> &'static str {
+    text(lang, "recurrence_end_count")
+}
+
+/// Short human-readable description of a rule, e.g. "Every 2 weeks: Mon, Wed".
+pub fn recurrence_summary(lang: Language, rule: &Recurrence) -> String {
+    let n = i64::from(rule.interval);
+    let mut out = if n == 1 {
+        recurrence_frequency(lang, Some(rule.frequency)).to_owned()
+    } else {
+        let key = match rule.frequency {
+            Frequency::Daily => "recurrence_every_n_days",
+            Frequency::Weekly => "recurrence_every_n_weeks",
+            Frequency::Monthly => "recurrence_every_n_months",
+            Frequency::Yearly => "recurrence_every_n_years",
+        };
+        plural(lang, key, n)
+    };
+
+    if !rule.by_weekday.is_empty() {
+        let names = date_picker_weekdays(lang);
+        let days: Vec<&str> = rule
+            .by_weekday
+            .iter()
+            .map(|d| names[d.num_days_from_monday() as usize])
+            .collect();
+        out.push_str(&format!(": {}", days.join(", ")));
+    }
+
+    if rule.by_month_day == [-1] {
+        out.push_str(&format!(", {}", recurrence_last_day(lang)));
+    }
+
+    if let Some(until) = rule.until {
+        let until = until.to_string();
+        let part = format(text(lang, "recurrence_until"), &[("until", &until)]);
+        out.push_str(&format!(" · {part}"));
     }
+
+    if let Some(count) = rule.count {
+        let count = count.to_string();
+        let part = format(text(lang, "recurrence_count_left"), &[("count", &count)]);
+        out.push_str(&format!(" · {part}"));
+    }
+
+    out
+}
+
+pub fn ui_planned(lang: Language) -> &'static str {
+    text(lang, "ui_planned")
+}
+
+pub fn ui_click_to_edit(lang: Language) -> &'static str {
+    text(lang, "ui_click_to_edit")
+}
+
+pub fn ui_save(lang: Language) -> &'static str {
+    text(lang, "ui_save")
+}
+
+pub fn ui_cancel(lang: Language) -> &'static str {
+    text(lang, "ui_cancel")
+}
+
+pub fn ui_overdue(lang: Language) -> &'static str {
+    text(lang, "ui_overdue")
+}
+
+pub fn ui_upcoming(lang: Language) -> &'static str {
+    text(lang, "ui_upcoming")
+}
+
+pub fn days_late(lang: Language, days: i64) -> String {
+    plural(lang, "days_late", days)
+}
+
+pub fn ui_mark_done(lang: Language) -> &'static str {
+    text(lang, "ui_mark_done")
+}
+
+pub fn ui_done(lang: Language) -> &'static str {
+    text(lang, "ui_done")
+}
+
+pub fn ui_done_at(lang: Language) -> &'static str {
+    text(lang, "ui_done_at")
+}
+
+pub fn ui_restore(lang: Language) -> &'static str {
+    text(lang, "ui_restore")
+}
+
+pub fn ui_snooze(lang: Language) -> &'static str {
+    text(lang, "ui_snooze")
+}
+
+pub fn ui_snooze_hours(lang: Language, hours: u32) -> String {
+    plural(lang, "ui_snooze_hours", i64::from(hours))
+}
+
+pub fn ui_snooze_tomorrow(lang: Language) -> &'static str {
+    text(lang, "ui_snooze_tomorrow")
+}
+
+pub fn ui_snooze_cancel(lang: Language) -> &'static str {
+    text(lang, "ui_snooze_cancel")
+}
+
+pub fn ui_snoozed_until(lang: Language) -> &'static str {
+    text(lang, "ui_snoozed_until")
 }
 
 pub fn ui_no_db(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Brak bazy danych",
-        Language::En => "Database unavailable",
-    }
+    text(lang, "ui_no_db")
+}
+
+pub fn ui_db_too_new(lang: Language) -> &'static str {
+    text(lang, "ui_db_too_new")
+}
+
+pub fn ui_db_problem(lang: Language) -> &'static str {
+    text(lang, "ui_db_problem")
+}
+
+pub fn ui_db_damaged(lang: Language) -> &'static str {
+    text(lang, "ui_db_damaged")
+}
+
+pub fn ui_db_unavailable(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_db_unavailable"), &[("error", error)])
+}
+
+pub fn ui_db_details(lang: Language) -> &'static str {
+    text(lang, "ui_db_details")
+}
+
+pub fn ui_recover(lang: Language) -> &'static str {
+    text(lang, "ui_recover")
+}
+
+pub fn ui_retry(lang: Language) -> &'static str {
+    text(lang, "ui_retry")
+}
+
+pub fn ui_recovered(lang: Language, count: usize, path: &str) -> String {
+    format(
+        &plural(lang, "ui_recovered", count as i64),
+        &[("path", path)],
+    )
+}
+
+pub fn ui_recovered_partly(lang: Language) -> &'static str {
+    text(lang, "ui_recovered_partly")
+}
+
+pub fn ui_recover_failed(lang: Language, error: &str) -> String {
+    format(text(lang, "ui_recover_failed"), &[("error", error)])
 }
 
 pub fn ui_empty(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "(pusto)",
-        Language::En => "(empty)",
-    }
+    text(lang, "ui_empty")
 }
 
 pub fn ui_db_read_error(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Błąd odczytu bazy",
-        Language::En => "Failed to read database",
-    }
+    text(lang, "ui_db_read_error")
 }
 
 pub fn tray_tooltip(_lang: Language) -> &'static str {
@@ -105,159 +552,124 @@ pub fn tray_tooltip(_lang: Language) -> &'static str {
 }
 
 pub fn footer(lang: Language, version: &str) -> String {
-    match lang {
-        Language::Pl => format!("© Jakub Konieczny. Wszelkie prawa zastrzeżone. v{version}"),
-        Language::En => format!("© Jakub Konieczny. All rights reserved. v{version}"),
-    }
+    format(text(lang, "footer"), &[("version", version)])
 }
 
 pub fn tray_open(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Otwórz",
-        Language::En => "Open",
-    }
+    text(lang, "tray_open")
 }
 
 pub fn tray_exit(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Zamknij",
-        Language::En => "Exit",
-    }
+    text(lang, "tray_exit")
 }
 
-pub fn notif_prefix(lang: Language, level: u8) -> &'static str {
-    match (lang, level) {
-        (Language::Pl, 1) => "≤ 7 dni",
-        (Language::Pl, 2) => "≤ 3 dni",
-        (Language::Pl, _) => "≤ 1 dzień",
-        (Language::En, 1) => "≤ 7 days",
-        (Language::En, 2) => "≤ 3 days",
-        (Language::En, _) => "≤ 1 day",
+/// A lead time in the largest whole unit: "7 days", "2 hours", "30 min".
+pub fn lead_time(lang: Language, minutes: i64) -> String {
+    const HOUR: i64 = 60;
+    const DAY: i64 = 24 * HOUR;
+
+    if minutes != 0 && minutes % DAY == 0 {
+        plural(lang, "lead_time_days", minutes / DAY)
+    } else if minutes != 0 && minutes % HOUR == 0 {
+        plural(lang, "lead_time_hours", minutes / HOUR)
+    } else {
+        format(
+            text(lang, "lead_time_minutes"),
+            &[("n", &minutes.to_string())],
+        )
     }
 }
 
-pub fn notif_title(lang: Language, level: u8) -> String {
-    match lang {
-        Language::Pl => format!("Przypomnienie ({})", notif_prefix(lang, level)),
-        Language::En => format!("Reminder ({})", notif_prefix(lang, level)),
+pub fn notif_prefix(lang: Language, offset_minutes: i64) -> String {
+    match offset_minutes {
+        0 => text(lang, "notif_now").to_owned(),
+        _ => format!("≤ {}", lead_time(lang, offset_minutes)),
     }
 }
 
+pub fn notif_title(lang: Language, offset_minutes: i64) -> String {
+    let when = notif_prefix(lang, offset_minutes);
+    format(text(lang, "notif_title"), &[("when", &when)])
+}
+
+pub fn notif_overdue_title(lang: Language, days: i64) -> String {
+    let late = days_late(lang, days);
+    format(text(lang, "notif_overdue_title"), &[("late", &late)])
+}
+
+pub fn notif_snooze_prompt(lang: Language) -> &'static str {
+    text(lang, "notif_snooze_prompt")
+}
+
+pub fn notif_snooze_action(lang: Language) -> &'static str {
+    text(lang, "notif_snooze_action")
+}
+
 pub fn notif_date_label(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Data",
-        Language::En => "Date",
-    }
+    text(lang, "notif_date_label")
 }
 
 pub fn date_picker_year(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Rok:",
-        Language::En => "Year:",
-    }
+    text(lang, "date_picker_year")
 }
 
 pub fn date_picker_week(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Tydz.",
-        Language::En => "Wk",
-    }
+    text(lang, "date_picker_week")
 }
 
 pub fn date_picker_weekdays(lang: Language) -> [&'static str; 7] {
-    match lang {
-        Language::Pl => ["Pn", "Wt", "Śr", "Cz", "Pt", "So", "Nd"],
-        Language::En => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
-    }
+    [
+        "weekday_mon",
+        "weekday_tue",
+        "weekday_wed",
+        "weekday_thu",
+        "weekday_fri",
+        "weekday_sat",
+        "weekday_sun",
+    ]
+    .map(|key| text(lang, key))
 }
 
 pub fn date_picker_cancel(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Anuluj",
-        Language::En => "Cancel",
-    }
+    text(lang, "date_picker_cancel")
 }
 
 pub fn date_picker_save(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "Zapisz",
-        Language::En => "Save",
-    }
+    text(lang, "date_picker_save")
 }
 
 pub fn date_picker_month_name(lang: Language, month: u32) -> &'static str {
-    match lang {
-        Language::Pl => match month {
-            1 => "Styczeń",
-            2 => "Luty",
-            3 => "Marzec",
-            4 => "Kwiecień",
-            5 => "Maj",
-            6 => "Czerwiec",
-            7 => "Lipiec",
-            8 => "Sierpień",
-            9 => "Wrzesień",
-            10 => "Październik",
-            11 => "Listopad",
-            12 => "Grudzień",
-            _ => "?",
-        },
-        Language::En => match month {
-            1 => "January",
-            2 => "February",
-            3 => "March",
-            4 => "April",
-            5 => "May",
-            6 => "June",
-            7 => "July",
-            8 => "August",
-            9 => "September",
-            10 => "October",
-            11 => "November",
-            12 => "December",
-            _ => "?",
-        },
+    const MONTHS: [&str; 12] = [
+        "month_1", "month_2", "month_3", "month_4", "month_5", "month_6", "month_7", "month_8",
+        "month_9", "month_10", "month_11", "month_12",
+    ];
+
+    match month {
+        1..=12 => text(lang, MONTHS[month as usize - 1]),
+        _ => "?",
     }
 }
 
 pub fn date_picker_hover_year_minus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "odejmij 1 rok",
-        Language::En => "subtract 1 year",
-    }
+    text(lang, "date_picker_hover_year_minus")
 }
 
 pub fn date_picker_hover_month_minus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "odejmij 1 miesiąc",
-        Language::En => "subtract 1 month",
-    }
+    text(lang, "date_picker_hover_month_minus")
 }
 
 pub fn date_picker_hover_day_minus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "odejmij 1 dzień",
-        Language::En => "subtract 1 day",
-    }
+    text(lang, "date_picker_hover_day_minus")
 }
 
 pub fn date_picker_hover_day_plus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "dodaj 1 dzień",
-        Language::En => "add 1 day",
-    }
+    text(lang, "date_picker_hover_day_plus")
 }
 
 pub fn date_picker_hover_month_plus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "dodaj 1 miesiąc",
-        Language::En => "add 1 month",
-    }
+    text(lang, "date_picker_hover_month_plus")
 }
 
 pub fn date_picker_hover_year_plus(lang: Language) -> &'static str {
-    match lang {
-        Language::Pl => "dodaj 1 rok",
-        Language::En => "add 1 year",
-    }
+    text(lang, "date_picker_hover_year_plus")
 }
@@ -0,0 +1,107 @@
+//! Translation catalogs from `locales/*.toml`, embedded by `build.rs`.
+//!
+//! The build already fails when a locale lacks a key or a plural form, and the tests call
+//! every accessor in every language, so the fallbacks here (the requested language, then
+//! English, then the key itself) only matter while a catalog is being edited.
+
+use super::Language;
+
+/// One catalog entry; plural forms are keyed by CLDR category (`one`, `few`, ...).
+pub enum Entry {
+    Text(&'static str),
+    Plural(&'static [(&'static str, &'static str)]),
+}
+
+mod embedded {
+    use super::Entry;
+
+    include!(concat!(env!("OUT_DIR"), "/embedded_locales.rs"));
+}
+
+/// The catalog of `lang`, sorted by key.
+fn catalog(lang: Language) -> &'static [(&'static str, Entry)] {
+    match lang {
+        Language::Pl => embedded::PL,
+        Language::En => embedded::EN,
+        Language::De => embedded::DE,
+        Language::Uk => embedded::UK,
+    }
+}
+
+fn lookup(lang: Language, key: &str) -> Option<&'static Entry> {
+    let catalog = catalog(lang);
+    catalog
+        .binary_search_by(|(k, _)| (*k).cmp(key))
+        .ok()
+        .map(|i| &catalog[i].1)
+}
+
+/// The message `key` in `lang`, falling back to English and then to the key.
+pub fn text(lang: Language, key: &'static str) -> &'static str {
+    [lang, Language::En]
+        .into_iter()
+        .find_map(|l| match lookup(l, key) {
+            Some(Entry::Text(text)) => Some(*text),
+            _ => None,
+        })
+        .unwrap_or(key)
+}
+
+/// The plural message `key` for the count `n`, with `{n}` filled in.
+pub fn plural(lang: Language, key: &'static str, n: i64) -> String {
+    let form = [lang, Language::En].into_iter().find_map(|l| {
+        let Some(Entry::Plural(forms)) = lookup(l, key) else {
+            return None;
+        };
+        let category = plural_category(l, n);
+        forms
+            .iter()
+            .find(|(c, _)| *c == category)
+            .or_else(|| forms.iter().find(|(c, _)| *c == "other"))
+            .map(|(_, text)| *text)
+    });
+
+    format(form.unwrap_or(key), &[("n", &n.to_string())])
+}
+
+/// CLDR plural category of a whole number (fractions never occur in the UI).
+fn plural_category(lang: Language, n: i64) -> &'static str {
+    let n = n.unsigned_abs();
+    let (n10, n100) = (n % 10, n % 100);
+    match lang {
+        Language::En | Language::De => {
+            if n == 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+        Language::Pl => {
+            if n == 1 {
+                "one"
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        Language::Uk => {
+            if n10 == 1 && n100 != 11 {
+                "one"
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+    }
+}
+
+/// Replaces `{name}` placeholders in `template`; unknown ones are left as they are.
+pub fn format(template: &str, args: &[(&str, &str)]) -> String {
+    let mut out = template.to_owned();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    out
+}
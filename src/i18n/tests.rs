@@ -0,0 +1,216 @@
+//! Every accessor, in every language, must find its message: a key missing from the
+//! catalogs (or mistyped in the accessor) falls back to the bare key, which these catch.
+
+use chrono::{NaiveDate, Weekday};
+
+use super::*;
+
+/// Fails when `s` is (or still contains) a catalog key or an unfilled placeholder.
+fn check(lang: Language, s: &str) {
+    let looks_like_key = s.split_whitespace().any(|word| {
+        word.contains('_')
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    });
+    assert!(!looks_like_key, "{lang:?}: untranslated key in {s:?}");
+    assert!(!s.contains('{'), "{lang:?}: unfilled placeholder in {s:?}");
+    assert!(!s.trim().is_empty(), "{lang:?}: empty message");
+}
+
+macro_rules! check_plain {
+    ($lang:expr, $($f:ident),+ $(,)?) => {
+        $(check($lang, $f($lang));)+
+    };
+}
+
+#[test]
+fn plain_messages() {
+    for lang in Language::ALL {
+        check_plain!(
+            lang,
+            ui_settings,
+            ui_start_with_system,
+            ui_autostart_failed,
+            ui_autostart_missing,
+            ui_autostart_unexpected,
+            ui_autostart_repair,
+            ui_autostart_ignore,
+            ui_language,
+            ui_language_system,
+            ui_theme,
+            ui_overdue_renag,
+            ui_notify_by,
+            sink_tray_balloon,
+            sink_message_box,
+            sink_desktop,
+            sink_log_file,
+            ui_default_alerts,
+            ui_custom_alerts,
+            alerts_hint,
+            ui_tags,
+            ui_new_tag_hint,
+            ui_tag_exists,
+            ui_delete_tag,
+            ui_filter_all,
+            ui_priority,
+            ui_data,
+            ui_export,
+            ui_import,
+            ui_import_confirm,
+            ui_backups_keep,
+            ui_restore_backup,
+            ui_no_backups,
+            ui_backup_too_new,
+            ui_add,
+            ui_add_button,
+            ui_note_hint,
+            ui_quick_add_hint,
+            ui_quick_add_no_date,
+            ui_quick_add_no_note,
+            ui_time_toggle,
+            recurrence_every,
+            recurrence_last_day,
+            recurrence_ends,
+            recurrence_end_never,
+            recurrence_end_until,
+            recurrence_end_count,
+            ui_planned,
+            ui_click_to_edit,
+            ui_save,
+            ui_cancel,
+            ui_overdue,
+            ui_upcoming,
+            ui_mark_done,
+            ui_done,
+            ui_done_at,
+            ui_restore,
+            ui_snooze,
+            ui_snooze_tomorrow,
+            ui_snooze_cancel,
+            ui_snoozed_until,
+            ui_no_db,
+            ui_db_too_new,
+            ui_db_problem,
+            ui_db_damaged,
+            ui_db_details,
+            ui_recover,
+            ui_retry,
+            ui_recovered_partly,
+            ui_empty,
+            ui_db_read_error,
+            tray_open,
+            tray_exit,
+            notif_snooze_prompt,
+            notif_snooze_action,
+            notif_date_label,
+            date_picker_year,
+            date_picker_week,
+            date_picker_cancel,
+            date_picker_save,
+            date_picker_hover_year_minus,
+            date_picker_hover_month_minus,
+            date_picker_hover_day_minus,
+            date_picker_hover_day_plus,
+            date_picker_hover_month_plus,
+            date_picker_hover_year_plus,
+            app_title,
+            app_header,
+            tray_tooltip,
+        );
+    }
+}
+
+#[test]
+fn named_values() {
+    for lang in Language::ALL {
+        for theme in Theme::ALL {
+            check(lang, theme_name(lang, theme));
+        }
+        for priority in Priority::ALL {
+            check(lang, priority_name(lang, priority));
+        }
+        for kind in [
+            BackupKind::Startup,
+            BackupKind::BeforeUpgrade,
+            BackupKind::BeforeRestore,
+        ] {
+            check(lang, backup_kind_name(lang, kind));
+        }
+        check(lang, recurrence_frequency(lang, None));
+        for frequency in Frequency::ALL {
+            check(lang, recurrence_frequency(lang, Some(frequency)));
+        }
+        for weekday in date_picker_weekdays(lang) {
+            check(lang, weekday);
+        }
+        for month in 1..=12 {
+            check(lang, date_picker_month_name(lang, month));
+        }
+    }
+}
+
+#[test]
+fn formatted_messages() {
+    for lang in Language::ALL {
+        // Enough counts to hit every plural category (one, few, many/other).
+        for n in [0, 1, 2, 5, 12, 21, 22, 25, 101] {
+            check(lang, &ui_exported(lang, n, "/tmp/out.csv"));
+            check(lang, &ui_import_ready(lang, n));
+            check(lang, &ui_import_skipped(lang, n, n, n));
+            check(lang, &ui_import_more_errors(lang, n));
+            check(lang, &ui_imported(lang, n));
+            check(lang, &ui_recovered(lang, n, "/tmp/quarantine"));
+
+            let n = n as i64;
+            check(
+                lang,
+                &ui_backup_entry(lang, "2026-11-03 09:00", "startup", n),
+            );
+            check(lang, &days_late(lang, n));
+            check(lang, &notif_overdue_title(lang, n.max(1)));
+            for unit in [1, 60, 24 * 60] {
+                check(lang, &lead_time(lang, n.max(1) * unit));
+                check(lang, &notif_title(lang, n * unit));
+            }
+        }
+        for hours in [1, 2, 5] {
+            check(lang, &ui_snooze_hours(lang, hours));
+        }
+
+        check(lang, &ui_autostart_elsewhere(lang, "/opt/app"));
+        check(lang, &ui_tag_save_failed(lang, "disk full"));
+        check(lang, &ui_export_failed(lang, "disk full"));
+        check(lang, &ui_import_failed(lang, "disk full"));
+        check(lang, &ui_import_unsupported_rule(lang, "FREQ=HOURLY"));
+        check(
+            lang,
+            &ui_backup_unreadable(lang, "2026-11-03 09:00", "startup"),
+        );
+        check(lang, &ui_restored(lang, "2026-11-03 09:00"));
+        check(lang, &ui_restore_failed(lang, "disk full"));
+        check(lang, &ui_db_unavailable(lang, "locked"));
+        check(lang, &ui_recover_failed(lang, "disk full"));
+        check(lang, &footer(lang, "1.0.0"));
+        check(lang, &notif_prefix(lang, 0));
+    }
+}
+
+#[test]
+fn recurrence_summaries() {
+    for lang in Language::ALL {
+        for frequency in Frequency::ALL {
+            for interval in [1, 2, 5] {
+                let mut rule = Recurrence::new(frequency);
+                rule.interval = interval;
+                check(lang, &recurrence_summary(lang, &rule));
+
+                rule.by_weekday = vec![Weekday::Mon, Weekday::Wed];
+                rule.by_month_day = vec![-1];
+                rule.until = NaiveDate::from_ymd_opt(2027, 1, 1);
+                rule.count = Some(3);
+                check(lang, &recurrence_summary(lang, &rule));
+            }
+        }
+    }
+}
@@ -0,0 +1,788 @@
+//! iCalendar (RFC 5545) export and import.
+//!
+//! Export writes each reminder as a `VEVENT` (or a `VTODO`, for task lists) with one
+//! `VALARM` per alert lead time, so calendar apps remind at the same moments the app does.
+//!
+//! Import reads `VEVENT`s and `VTODO`s:
+//! - `DTSTART` (events) or `DUE`, else `DTSTART` (to-dos) -> date and time; all-day values
+//!   become reminders without a time. UTC times are converted to local time; times with a
+//!   `TZID` are taken as local wall-clock time (there is no time zone database here).
+//! - `SUMMARY` -> note, with `DESCRIPTION` appended on the same line.
+//! - `RRULE` -> repeat rule, as far as [`Recurrence`] supports it.
+//! - `VALARM` triggers before the start -> alert lead times.
+//! - `UID` -> kept, so importing the same file twice doesn't duplicate reminders.
+//! - `PRIORITY` -> priority: 1 is critical, 2-4 high, 6-9 low, anything else normal.
+//! - `CATEGORIES` -> tags.
+
+use std::fmt::{self, Write as _};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone as _, Utc};
+
+use crate::alerts;
+use crate::db_operations::{Priority, Reminder};
+use crate::recurrence::Recurrence;
+use crate::transfer::Item;
+
+const PRODID: &str = "-//SilliReminder//SilliReminder//EN";
+
+/// Which calendar component reminders are exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Event,
+    Todo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIcalError(String);
+
+impl fmt::Display for ParseIcalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid iCalendar file: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseIcalError {}
+
+/// A whole `VCALENDAR` with one component per reminder.
+pub fn export(reminders: &[Reminder], component: Component) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, &format!("PRODID:{PRODID}"));
+    line(&mut out, "CALSCALE:GREGORIAN");
+
+    let stamp = format_utc(Utc::now().naive_utc());
+    for r in reminders {
+        export_reminder(&mut out, r, component, &stamp);
+    }
+
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn export_reminder(out: &mut String, r: &Reminder, component: Component, stamp: &str) {
+    let name = match component {
+        Component::Event => "VEVENT",
+        Component::Todo => "VTODO",
+    };
+    let uid = match &r.uid {
+        Some(uid) => uid.clone(),
+        None => format!("{}@sillireminder", r.id),
+    };
+
+    line(out, &format!("BEGIN:{name}"));
+    line(out, &format!("UID:{}", escape(&uid)));
+    line(out, &format!("DTSTAMP:{stamp}"));
+
+    let when = match r.time {
+        Some(time) => format!(":{}", r.date.and_time(time).format("%Y%m%dT%H%M%S")),
+        None => format!(";VALUE=DATE:{}", r.date.format("%Y%m%d")),
+    };
+    match component {
+        Component::Event => {
+            line(out, &format!("DTSTART{when}"));
+            if r.time.is_none()
+                && let Some(next_day) = r.date.succ_opt()
+            {
+                line(
+                    out,
+                    &format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+                );
+            }
+        }
+        Component::Todo => line(out, &format!("DUE{when}")),
+    }
+
+    line(out, &format!("SUMMARY:{}", escape(&r.note)));
+    if let Some(rule) = &r.recurrence {
+        line(
+            out,
+            &format!("RRULE:{}", format_rule(rule, r.time.is_some())),
+        );
+    }
+    let priority = match r.priority {
+        Priority::Low => Some(9),
+        Priority::Normal => None,
+        Priority::High => Some(3),
+        Priority::Critical => Some(1),
+    };
+    if let Some(priority) = priority {
+        line(out, &format!("PRIORITY:{priority}"));
+    }
+    if !r.tags.is_empty() {
+        let names: Vec<String> = r.tags.iter().map(|t| escape(&t.name)).collect();
+        line(out, &format!("CATEGORIES:{}", names.join(",")));
+    }
+    if component == Component::Todo {
+        match r.completed_at {
+            Some(at) => {
+                line(out, "STATUS:COMPLETED");
+                line(out, &format!("COMPLETED:{}", format_utc(local_to_utc(at))));
+            }
+            None => line(out, "STATUS:NEEDS-ACTION"),
+        }
+    }
+
+    // A to-do has no start; its alarms are relative to `DUE`.
+    let related = match component {
+        Component::Event => "",
+        Component::Todo => ";RELATED=END",
+    };
+    for alert in &r.alerts {
+        line(out, "BEGIN:VALARM");
+        line(out, "ACTION:DISPLAY");
+        line(out, &format!("DESCRIPTION:{}", escape(&r.note)));
+        line(
+            out,
+            &format!(
+                "TRIGGER{related}:{}",
+                format_duration(-alert.offset_minutes)
+            ),
+        );
+        line(out, "END:VALARM");
+    }
+
+    line(out, &format!("END:{name}"));
+}
+
+/// The `RRULE` value. `UNTIL` must take the same form as `DTSTART` (RFC 5545 §3.3.10), so
+/// for a timed reminder it becomes the last second of that day, in local time like `DTSTART`.
+fn format_rule(rule: &Recurrence, timed: bool) -> String {
+    match rule.until {
+        Some(until) if timed => {
+            let rule = Recurrence {
+                until: None,
+                ..rule.clone()
+            };
+            format!("{rule};UNTIL={}T235959", until.format("%Y%m%d"))
+        }
+        _ => rule.to_string(),
+    }
+}
+
+/// Appends a content line, folded at 75 octets as RFC 5545 requires.
+fn line(out: &mut String, content: &str) {
+    let mut width = 0;
+    for c in content.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The unescaped values of a comma-separated list such as `CATEGORIES`, trimmed.
+fn split_list(text: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(unescape(&text[start..i]).trim().to_owned());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(unescape(&text[start..]).trim().to_owned());
+    values
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+fn local_to_utc(at: NaiveDateTime) -> NaiveDateTime {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map_or(at, |t| t.naive_utc())
+}
+
+fn format_utc(at: NaiveDateTime) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// `-P7D`, `-PT2H`, `-P1DT30M`, `PT0S`.
+fn format_duration(minutes: i64) -> String {
+    if minutes == 0 {
+        return "PT0S".to_owned();
+    }
+
+    let mut out = String::new();
+    if minutes < 0 {
+        out.push('-');
+    }
+    out.push('P');
+
+    let minutes = minutes.unsigned_abs();
+    let (days, rest) = (minutes / alerts::DAY as u64, minutes % alerts::DAY as u64);
+    let (hours, mins) = (rest / alerts::HOUR as u64, rest % alerts::HOUR as u64);
+    if days > 0 {
+        let _ = write!(out, "{days}D");
+    }
+    if hours > 0 || mins > 0 {
+        out.push('T');
+        if hours > 0 {
+            let _ = write!(out, "{hours}H");
+        }
+        if mins > 0 {
+            let _ = write!(out, "{mins}M");
+        }
+    }
+    out
+}
+
+/// A duration such as `-P1W`, `-PT15M` or `P1DT2H`, in whole minutes (seconds dropped).
+fn parse_duration(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'-' => (-1, &value[1..]),
+        b'+' => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut minutes = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                // Overflowing values (`P99999999999999W`) are as invalid as malformed ones.
+                let part = match (c, in_time) {
+                    ('W', false) => n.checked_mul(7 * alerts::DAY),
+                    ('D', false) => n.checked_mul(alerts::DAY),
+                    ('H', true) => n.checked_mul(alerts::HOUR),
+                    ('M', true) => Some(n),
+                    ('S', true) => Some(n / 60),
+                    _ => None,
+                }?;
+                minutes = minutes.checked_add(part)?;
+            }
+        }
+    }
+    number.is_empty().then_some(sign * minutes)
+}
+
+/// One unfolded content line: `NAME;PARAM=VALUE:value`.
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, &'a str)>,
+    value: &'a str,
+}
+
+impl<'a> Property<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        // The value starts at the first ':' outside a quoted parameter value.
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                quoted = !quoted;
+                None
+            }
+            ':' if !quoted => Some(i),
+            _ => None,
+        })?;
+
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.to_ascii_uppercase();
+        let params = parts
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_ascii_uppercase(), v.trim_matches('"')))
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value,
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&'a str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| *v)
+    }
+}
+
+/// A date or date-time value.
+#[derive(Clone, Copy)]
+enum When {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+fn parse_when(prop: &Property<'_>) -> Option<When> {
+    let value = prop.value.trim();
+    if prop.param("VALUE") == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(When::Date);
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(When::DateTime(
+            Utc.from_utc_datetime(&at)
+                .with_timezone(&Local)
+                .naive_local(),
+        ));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .map(When::DateTime)
+}
+
+/// What is collected while inside one `VEVENT` / `VTODO`.
+#[derive(Default)]
+struct Draft {
+    todo: bool,
+    uid: Option<String>,
+    start: Option<When>,
+    due: Option<When>,
+    summary: String,
+    description: String,
+    rule: Option<String>,
+    priority: Priority,
+    tags: Vec<String>,
+    alerts: Vec<i64>,
+    completed: bool,
+    completed_at: Option<NaiveDateTime>,
+    /// A changed instance of a recurring entry (`RECURRENCE-ID`); only the series is kept.
+    is_override: bool,
+}
+
+impl Draft {
+    fn finish(self) -> Option<Item> {
+        if self.is_override {
+            return None;
+        }
+        let when = if self.todo {
+            self.due.or(self.start)
+        } else {
+            self.start
+        }?;
+        let (date, time) = match when {
+            When::Date(d) => (d, None),
+            When::DateTime(dt) => (dt.date(), Some(dt.time())),
+        };
+
+        let summary = self.summary.trim();
+        let description = self.description.split_whitespace().collect::<Vec<_>>();
+        let description = description.join(" ");
+        let note = match (summary.is_empty(), description.is_empty()) {
+            (false, false) => format!("{summary} - {description}"),
+            (false, true) => summary.to_owned(),
+            (true, false) => description,
+            (true, true) => return None,
+        };
+
+        let (recurrence, unsupported_rule) = match self.rule {
+            Some(rule) => match rule.parse::<Recurrence>() {
+                Ok(r) => (Some(r), None),
+                Err(_) => (None, Some(rule)),
+            },
+            None => (None, None),
+        };
+
+        let completed_at = if self.completed || self.completed_at.is_some() {
+            Some(
+                self.completed_at
+                    .unwrap_or_else(|| Local::now().naive_local()),
+            )
+        } else {
+            None
+        };
+
+        Some(Item {
+            uid: self.uid,
+            date,
+            time,
+            note,
+            recurrence,
+            unsupported_rule,
+            priority: self.priority,
+            tags: self.tags,
+            alerts: (!self.alerts.is_empty()).then_some(self.alerts),
+            alerts_fired: Vec::new(),
+            snoozed_until: None,
+            completed_at,
+        })
+    }
+}
+
+/// Reads every `VEVENT` and `VTODO` with a usable date from `text`.
+pub fn parse(text: &str) -> Result<Vec<Item>, ParseIcalError> {
+    // Unfold: a line starting with a space or tab continues the previous one.
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match raw.strip_prefix([' ', '\t']) {
+            Some(cont) if !lines.is_empty() => lines.last_mut().unwrap().push_str(cont),
+            _ => lines.push(raw.to_owned()),
+        }
+    }
+
+    if !lines
+        .iter()
+        .any(|l| l.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(ParseIcalError("no BEGIN:VCALENDAR".into()));
+    }
+
+    let mut items = Vec::new();
+    let mut draft: Option<Draft> = None;
+    let mut in_alarm = false;
+    let mut alarm_trigger: Option<i64> = None;
+
+    for l in &lines {
+        let Some(prop) = Property::parse(l) else {
+            continue;
+        };
+        let value = prop.value.to_ascii_uppercase();
+
+        match (prop.name.as_str(), value.as_str()) {
+            ("BEGIN", "VEVENT" | "VTODO") => {
+                draft = Some(Draft {
+                    todo: value == "VTODO",
+                    ..Draft::default()
+                });
+            }
+            ("END", "VEVENT" | "VTODO") => {
+                if let Some(item) = draft.take().and_then(Draft::finish) {
+                    items.push(item);
+                }
+            }
+            ("BEGIN", "VALARM") => {
+                in_alarm = true;
+                alarm_trigger = None;
+            }
+            ("END", "VALARM") => {
+                in_alarm = false;
+                if let (Some(d), Some(before)) = (draft.as_mut(), alarm_trigger.take())
+                    && !d.alerts.contains(&before)
+                {
+                    d.alerts.push(before);
+                }
+            }
+            _ => {
+                let Some(d) = draft.as_mut() else {
+                    continue;
+                };
+                if in_alarm {
+                    // Only triggers relative to the start (or due time), at or before it
+                    // and no further ahead than the app allows.
+                    if prop.name == "TRIGGER"
+                        && prop.param("VALUE") != Some("DATE-TIME")
+                        && let Some(minutes) = parse_duration(prop.value.trim())
+                        && (-alerts::MAX_OFFSET..=0).contains(&minutes)
+                    {
+                        alarm_trigger = Some(-minutes);
+                    }
+                    continue;
+                }
+
+                match prop.name.as_str() {
+                    "UID" => d.uid = Some(unescape(prop.value)),
+                    "DTSTART" => d.start = parse_when(&prop),
+                    "DUE" => d.due = parse_when(&prop),
+                    "SUMMARY" => d.summary = unescape(prop.value),
+                    "DESCRIPTION" => d.description = unescape(prop.value),
+                    "RRULE" => d.rule = Some(prop.value.to_owned()),
+                    "RECURRENCE-ID" => d.is_override = true,
+                    "PRIORITY" => {
+                        d.priority = match value.trim().parse::<u8>() {
+                            Ok(1) => Priority::Critical,
+                            Ok(2..=4) => Priority::High,
+                            Ok(6..=9) => Priority::Low,
+                            _ => Priority::Normal,
+                        };
+                    }
+                    "CATEGORIES" => {
+                        for name in split_list(prop.value) {
+                            if !name.is_empty() && !d.tags.contains(&name) {
+                                d.tags.push(name);
+                            }
+                        }
+                    }
+                    "STATUS" => d.completed = value == "COMPLETED",
+                    "COMPLETED" => {
+                        d.completed_at = match parse_when(&prop) {
+                            Some(When::DateTime(at)) => Some(at),
+                            Some(When::Date(day)) => Some(day.and_time(NaiveTime::MIN)),
+                            None => None,
+                        };
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    for item in &mut items {
+        if let Some(offsets) = &mut item.alerts {
+            offsets.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db_operations::{Alert, Tag};
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn reminder(time: Option<NaiveTime>) -> Reminder {
+        Reminder {
+            id: 7,
+            date: date(2026, 10, 14),
+            time,
+            note: "Pay rent, water; gas \\ electricity - and a note long enough to be folded"
+                .into(),
+            recurrence: Some("FREQ=MONTHLY;UNTIL=20271231".parse().unwrap()),
+            completed_at: None,
+            overdue_notified_on: None,
+            snoozed_until: None,
+            custom_alerts: true,
+            alerts: [alerts::DAY, 0]
+                .into_iter()
+                .map(|offset_minutes| Alert {
+                    offset_minutes,
+                    fired_at: None,
+                })
+                .collect(),
+            uid: Some("rent@example.com".into()),
+            tags: ["bills", "a, b"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| Tag {
+                    id: i as i64 + 1,
+                    name: name.into(),
+                    color: [0, 0, 0],
+                    alert_offsets: None,
+                })
+                .collect(),
+            priority: Priority::High,
+        }
+    }
+
+    fn calendar(body: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{body}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn folds_long_lines_and_unfolds_them() {
+        let content = format!("SUMMARY:{}", "żółw ".repeat(40));
+        let mut out = String::new();
+        line(&mut out, &content);
+        assert!(out.lines().count() > 1);
+        assert!(out.split("\r\n").all(|l| l.len() <= 75), "{out:?}");
+
+        let text = calendar(&format!(
+            "BEGIN:VTODO\r\nDUE;VALUE=DATE:20261014\r\n{out}END:VTODO\r\n"
+        ));
+        let items = parse(&text).unwrap();
+        assert_eq!(items[0].note, content["SUMMARY:".len()..].trim());
+
+        // A tab continues a line too.
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:2026\r\n\t1014T093000\r\nSUMMARY:x\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(
+            parse(&text).unwrap()[0].time,
+            NaiveTime::from_hms_opt(9, 30, 0)
+        );
+    }
+
+    #[test]
+    fn escapes_text() {
+        let text = "a,b;c\\d\r\ne";
+        assert_eq!(escape(text), r"a\,b\;c\\d\ne");
+        assert_eq!(unescape(&escape(text)), "a,b;c\\d\ne");
+        assert_eq!(unescape("line\\Nbreak\\"), "line\nbreak");
+        assert_eq!(
+            split_list("work, a\\,b ,,home"),
+            ["work", "a,b", "", "home"]
+        );
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("-P1W"), Some(-7 * alerts::DAY));
+        assert_eq!(parse_duration("-PT15M"), Some(-15));
+        assert_eq!(
+            parse_duration("+P1DT2H"),
+            Some(alerts::DAY + 2 * alerts::HOUR)
+        );
+        assert_eq!(parse_duration("-PT90S"), Some(-1));
+        assert_eq!(parse_duration("PT0S"), Some(0));
+        for invalid in [
+            "",
+            "P1H",
+            "PT1D",
+            "1D",
+            "P1",
+            "-PXD",
+            "-P999999999999999W",
+            "P9223372036854775807D",
+            "PT9223372036854775807H",
+            "PT9223372036854775807M1M",
+        ] {
+            assert_eq!(parse_duration(invalid), None, "{invalid:?}");
+        }
+
+        for minutes in [
+            0,
+            -15,
+            -2 * alerts::HOUR,
+            -alerts::DAY,
+            -(alerts::DAY + 90),
+            45,
+        ] {
+            assert_eq!(parse_duration(&format_duration(minutes)), Some(minutes));
+        }
+    }
+
+    #[test]
+    fn parses_dates_and_date_times() {
+        let when = |line: &str| parse_when(&Property::parse(line).unwrap());
+        let at = date(2026, 10, 14).and_hms_opt(9, 30, 0).unwrap();
+
+        assert!(
+            matches!(when("DTSTART;VALUE=DATE:20261014"), Some(When::Date(d)) if d == at.date())
+        );
+        assert!(matches!(when("DTSTART:20261014"), Some(When::Date(d)) if d == at.date()));
+        assert!(matches!(when("DTSTART:20261014T093000"), Some(When::DateTime(t)) if t == at));
+        assert!(matches!(
+            when("DTSTART;TZID=\"Europe/Warsaw\":20261014T093000"),
+            Some(When::DateTime(t)) if t == at
+        ));
+        let local = Utc
+            .from_utc_datetime(&at)
+            .with_timezone(&Local)
+            .naive_local();
+        assert!(matches!(when("DTSTART:20261014T093000Z"), Some(When::DateTime(t)) if t == local));
+        assert!(when("DTSTART;VALUE=DATE:20261014T093000").is_none());
+        assert!(when("DTSTART:tomorrow").is_none());
+    }
+
+    #[test]
+    fn reads_alarms_and_categories() {
+        let text = calendar(concat!(
+            "BEGIN:VEVENT\r\n",
+            "DTSTART:20261014T093000\r\n",
+            "SUMMARY:Dentist\r\n",
+            "CATEGORIES:health,a\\,b\r\n",
+            "CATEGORIES:Home,health\r\n",
+            "BEGIN:VALARM\r\nTRIGGER:-PT2H\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER;RELATED=START:-P1D\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER:-P1D\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER:PT0S\r\nEND:VALARM\r\n",
+            // After the start, absolute, too far ahead or unreadable: ignored.
+            "BEGIN:VALARM\r\nTRIGGER:PT1H\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER;VALUE=DATE-TIME:20261013T090000Z\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER:-P99999999999999W\r\nEND:VALARM\r\n",
+            "BEGIN:VALARM\r\nTRIGGER:-P999999999999999W\r\nEND:VALARM\r\n",
+            "END:VEVENT\r\n",
+            "BEGIN:VEVENT\r\nDTSTART:20261015\r\nSUMMARY:No alarms\r\nEND:VEVENT\r\n",
+        ));
+        let items = parse(&text).unwrap();
+
+        assert_eq!(
+            items[0].alerts,
+            Some(vec![alerts::DAY, 2 * alerts::HOUR, 0])
+        );
+        assert_eq!(items[0].tags, ["health", "a,b", "Home"]);
+        assert_eq!(items[1].alerts, None);
+        assert!(items[1].tags.is_empty());
+    }
+
+    #[test]
+    fn writes_until_in_the_form_of_the_start() {
+        let timed = export(
+            &[reminder(NaiveTime::from_hms_opt(9, 30, 0))],
+            Component::Event,
+        );
+        assert!(timed.contains("DTSTART:20261014T093000\r\n"));
+        assert!(timed.contains("RRULE:FREQ=MONTHLY;UNTIL=20271231T235959\r\n"));
+
+        let all_day = export(&[reminder(None)], Component::Event);
+        assert!(all_day.contains("DTSTART;VALUE=DATE:20261014\r\n"));
+        assert!(all_day.contains("RRULE:FREQ=MONTHLY;UNTIL=20271231\r\n"));
+    }
+
+    #[test]
+    fn round_trips_exported_reminders() {
+        let time = NaiveTime::from_hms_opt(9, 30, 0);
+        let mut done = reminder(None);
+        done.completed_at = date(2026, 10, 13).and_hms_opt(18, 5, 0);
+        done.alerts.retain(|a| a.offset_minutes != 0);
+
+        for (r, component) in [
+            (reminder(time), Component::Event),
+            (reminder(None), Component::Event),
+            (reminder(time), Component::Todo),
+            (done, Component::Todo),
+        ] {
+            let items = parse(&export(std::slice::from_ref(&r), component)).unwrap();
+            let offsets = r.alerts.iter().map(|a| a.offset_minutes).collect();
+            let expected = Item {
+                uid: r.uid.clone(),
+                date: r.date,
+                time: r.time,
+                note: r.note.clone(),
+                recurrence: r.recurrence.clone(),
+                unsupported_rule: None,
+                priority: r.priority,
+                tags: r.tags.iter().map(|t| t.name.clone()).collect(),
+                alerts: Some(offsets),
+                alerts_fired: Vec::new(),
+                snoozed_until: None,
+                completed_at: r.completed_at,
+            };
+            assert_eq!(items, [expected], "{component:?}");
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Single-instance guard and the local IPC channel to the running instance.
+//!
+//! The first launch holds an exclusive lock on `instance.lock` in the app data directory
+//! and listens for commands (a Unix domain socket in a private directory next to the lock
+//! on Linux, a named pipe named after the user's SID on Windows). A later launch finds the
+//! lock taken, forwards what it was asked to do and exits, so there is only ever one tray
+//! icon and one notification loop per database.
+//!
+//! The protocol is one line per connection: `open` ([`TrayCommand::Open`]) or `reload`
+//! ([`TrayCommand::Reload`], after the command line changed the database).
+
+use std::error::Error;
+use std::fs::File;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::tray::{self, TrayCommand};
+use crate::{debug_err, debug_log, paths};
+
+/// How often a launch retries reaching a listener that is busy or still starting up.
+const SEND_ATTEMPTS: u32 = 10;
+const SEND_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Held by the running instance for as long as it lives; the OS drops the lock when the
+/// process exits, even after a crash.
+pub struct InstanceLock {
+    _file: File,
+}
+
+/// Takes the single-instance lock, or returns `None` when another instance holds it.
+pub fn acquire() -> Result<Option<InstanceLock>, Box<dyn Error>> {
+    let dir = paths::app_data_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join("instance.lock"))?;
+
+    match file.try_lock() {
+        Ok(()) => Ok(Some(InstanceLock { _file: file })),
+        Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+        Err(std::fs::TryLockError::Error(err)) => Err(Box::new(err)),
+    }
+}
+
+/// Whether another instance is listening for commands. This only connects to it: probing
+/// the lock would hold it for a moment, and a GUI starting right then would give up.
+pub fn is_running() -> bool {
+    platform::probe()
+}
+
+/// Starts accepting commands from later launches and forwards them to `sender`. Needs the
+/// lock, so that only the running instance listens.
+pub fn listen(_lock: &InstanceLock, sender: Sender<TrayCommand>) {
+    let handle = move |line: &str| {
+        // A probe from `is_running` connects without sending anything.
+        if line.trim().is_empty() {
+            return;
+        }
+        let Some(cmd) = decode(line) else {
+            debug_err!("ignoring unknown instance command: {line:?}");
+            return;
+        };
+        debug_log!("instance command: {cmd:?}");
+        tray::send_command(&sender, cmd);
+    };
+
+    if let Err(err) = platform::listen(handle) {
+        debug_err!("failed to listen for other instances: {err}");
+    }
+}
+
+/// Forwards `cmd` to the running instance.
+pub fn send(cmd: TrayCommand) -> Result<(), Box<dyn Error>> {
+    let line = encode(cmd).ok_or("command can't be sent to another instance")?;
+
+    let mut attempt = 1;
+    loop {
+        match platform::send(line) {
+            Ok(()) => return Ok(()),
+            Err(_) if attempt < SEND_ATTEMPTS => {
+                attempt += 1;
+                std::thread::sleep(SEND_RETRY_DELAY);
+            }
+            Err(err) => return Err(Box::new(err)),
+        }
+    }
+}
+
+fn encode(cmd: TrayCommand) -> Option<&'static str> {
+    match cmd {
+        TrayCommand::Open => Some("open"),
+        TrayCommand::Reload => Some("reload"),
+        TrayCommand::Exit | TrayCommand::Snooze { .. } => None,
+    }
+}
+
+fn decode(line: &str) -> Option<TrayCommand> {
+    match line.trim() {
+        "open" => Some(TrayCommand::Open),
+        "reload" => Some(TrayCommand::Reload),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+#[path = "instance/unix.rs"]
+mod platform;
+
+#[cfg(windows)]
+#[path = "instance/windows.rs"]
+mod platform;
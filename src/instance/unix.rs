@@ -0,0 +1,64 @@
+//! Instance IPC over a Unix domain socket in a private directory next to the lock file.
+
+use std::fs::{DirBuilder, Permissions};
+use std::io::{self, BufRead as _, BufReader, Read as _, Write as _};
+use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::paths;
+
+/// Longest command line accepted; anything longer isn't one of ours.
+const MAX_LINE: u64 = 256;
+
+/// Only the owner may enter it, so the socket is never reachable by others, not even in
+/// the moment between creating it and tightening its own permissions.
+fn runtime_dir() -> PathBuf {
+    paths::app_data_dir().join("run")
+}
+
+fn socket_path() -> PathBuf {
+    runtime_dir().join("instance.sock")
+}
+
+pub(super) fn listen(handle: impl Fn(&str) + Send + 'static) -> io::Result<()> {
+    let dir = runtime_dir();
+    DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+    // It may predate us with looser permissions.
+    std::fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
+
+    let path = socket_path();
+    // Left behind by an instance that didn't exit cleanly; we hold the lock, so it's stale.
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            // Don't let a client that never finishes its line block everyone else.
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+
+            let mut line = String::new();
+            if BufReader::new(stream.take(MAX_LINE))
+                .read_line(&mut line)
+                .is_ok()
+            {
+                handle(&line);
+            }
+        }
+    });
+    Ok(())
+}
+
+pub(super) fn probe() -> bool {
+    UnixStream::connect(socket_path()).is_ok()
+}
+
+pub(super) fn send(line: &str) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    stream.write_all(format!("{line}\n").as_bytes())
+}
@@ -0,0 +1,171 @@
+//! Instance IPC over a per-user named pipe.
+//!
+//! The pipe is named after the user's SID, which (unlike `USERNAME`) other users can't
+//! choose. It is inbound-only and rejects remote clients; its default security descriptor
+//! only lets the creating user write to it. The first instance is created with
+//! `FILE_FLAG_FIRST_PIPE_INSTANCE`, so listening fails instead of sharing the name with a
+//! pipe someone else created first.
+
+use std::io::{self, Write as _};
+
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED, HANDLE, HLOCAL, LocalFree,
+};
+use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+use windows::Win32::Security::{GetTokenInformation, TOKEN_QUERY, TOKEN_USER, TokenUser};
+use windows::Win32::Storage::FileSystem::{
+    FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAGS_AND_ATTRIBUTES, PIPE_ACCESS_INBOUND, ReadFile,
+};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE,
+    PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+use windows::Win32::UI::WindowsAndMessaging::{ASFW_ANY, AllowSetForegroundWindow};
+use windows::core::{HSTRING, PWSTR};
+
+/// Longest command line accepted; anything longer isn't one of ours.
+const MAX_LINE: usize = 256;
+
+fn pipe_name() -> io::Result<String> {
+    Ok(format!(r"\\.\pipe\SilliReminder-{}", user_sid()?))
+}
+
+/// The SID of the user running this process, e.g. `S-1-5-21-...-1001`.
+fn user_sid() -> io::Result<String> {
+    let mut token = HANDLE::default();
+    unsafe {
+        // SAFETY: Win32 FFI call; `token` receives a handle closed below.
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+    }
+
+    let mut len = 0u32;
+    // `u64`s, so the `TOKEN_USER` at its start is aligned.
+    let mut buf: Vec<u64> = Vec::new();
+    let info = unsafe {
+        // SAFETY: the first call only reports the size; the second writes at most `len`
+        // bytes into `buf`.
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut len);
+        buf.resize((len as usize).div_ceil(8), 0);
+        let info = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buf.as_mut_ptr().cast()),
+            len,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        info
+    };
+    info?;
+
+    let mut text = PWSTR::null();
+    unsafe {
+        // SAFETY: `buf` holds a `TOKEN_USER` whose SID points into `buf`, which outlives
+        // the call; the string is freed with `LocalFree` as documented.
+        let user = &*buf.as_ptr().cast::<TOKEN_USER>();
+        ConvertSidToStringSidW(user.User.Sid, &mut text)?;
+        let sid = text.to_string();
+        let _ = LocalFree(Some(HLOCAL(text.0.cast())));
+        sid.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+pub(super) fn listen(handle: impl Fn(&str) + Send + 'static) -> io::Result<()> {
+    let name = HSTRING::from(pipe_name()?);
+    // Create the first pipe instance up front so a failure is reported to the caller.
+    // `HANDLE` isn't `Send`; the thread gets the raw value.
+    let raw = create_pipe(&name, FILE_FLAG_FIRST_PIPE_INSTANCE)?.0 as isize;
+
+    std::thread::spawn(move || {
+        let mut pipe = HANDLE(raw as *mut core::ffi::c_void);
+        loop {
+            let line = read_line(pipe);
+            // Open the next instance while this one still exists, so the name is never
+            // free for another process to take.
+            let next = create_pipe(&name, FILE_FLAGS_AND_ATTRIBUTES(0));
+            unsafe {
+                // SAFETY: `pipe` is a valid handle owned by this loop.
+                let _ = DisconnectNamedPipe(pipe);
+                let _ = CloseHandle(pipe);
+            }
+            if let Some(line) = line {
+                handle(&line);
+            }
+
+            pipe = match next {
+                Ok(pipe) => pipe,
+                Err(err) => {
+                    crate::debug_err!("instance pipe failed: {err}");
+                    return;
+                }
+            };
+        }
+    });
+    Ok(())
+}
+
+fn create_pipe(name: &HSTRING, flags: FILE_FLAGS_AND_ATTRIBUTES) -> io::Result<HANDLE> {
+    let pipe = unsafe {
+        // SAFETY: Win32 FFI call with a valid, NUL-terminated name.
+        CreateNamedPipeW(
+            name,
+            PIPE_ACCESS_INBOUND | flags,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            0,
+            MAX_LINE as u32,
+            0,
+            None,
+        )
+    };
+    if pipe.is_invalid() {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(pipe)
+}
+
+/// Waits for a client and reads its line.
+fn read_line(pipe: HANDLE) -> Option<String> {
+    let connected = unsafe {
+        // SAFETY: `pipe` is a valid pipe handle; no overlapped I/O.
+        ConnectNamedPipe(pipe, None)
+    };
+    // A client that connected between create and connect is reported as an error.
+    if let Err(err) = connected
+        && err.code() != ERROR_PIPE_CONNECTED.to_hresult()
+    {
+        return None;
+    }
+
+    let mut buf = [0u8; MAX_LINE];
+    let mut len = 0u32;
+    unsafe {
+        // SAFETY: `buf` outlives the call; no overlapped I/O.
+        ReadFile(pipe, Some(&mut buf), Some(&mut len), None).ok()?;
+    }
+    Some(String::from_utf8_lossy(&buf[..len as usize]).into_owned())
+}
+
+pub(super) fn probe() -> bool {
+    let Ok(name) = pipe_name() else {
+        return false;
+    };
+    match std::fs::OpenOptions::new().write(true).open(name) {
+        Ok(_) => true,
+        // Every instance is serving another client, so someone is listening.
+        Err(err) => err.raw_os_error() == Some(ERROR_PIPE_BUSY.0 as i32),
+    }
+}
+
+pub(super) fn send(line: &str) -> io::Result<()> {
+    // Let the running instance bring its window to the front; Windows only allows that
+    // for a process the foreground process allows it for.
+    unsafe {
+        // SAFETY: Win32 FFI call without pointers.
+        let _ = AllowSetForegroundWindow(ASFW_ANY);
+    }
+
+    let mut pipe = std::fs::OpenOptions::new().write(true).open(pipe_name()?)?;
+    pipe.write_all(format!("{line}\n").as_bytes())
+}
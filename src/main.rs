@@ -1,18 +1,29 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod alerts;
 mod app;
 mod autostart;
+mod cli;
 mod db_operations;
 mod debug_log;
 mod i18n;
+mod ical;
+mod instance;
 mod paths;
+mod quick_add;
+mod records;
+mod recurrence;
 mod settings;
+mod sinks;
+mod transfer;
 mod tray;
 mod widgets;
 
 use eframe::egui;
 use std::sync::mpsc;
 
+use tray::TrayCommand;
+
 include!(concat!(env!("OUT_DIR"), "/embedded_icon_png.rs"));
 
 fn window_icon() -> Option<egui::IconData> {
@@ -23,35 +34,51 @@ fn window_icon() -> Option<egui::IconData> {
     eframe::icon_data::from_png_bytes(ICON_PNG).ok()
 }
 
-fn is_background_mode() -> bool {
-    std::env::args().any(|arg| arg == "--background" || arg == "--autostart")
-}
-
-fn is_autostart_launch() -> bool {
-    std::env::args().any(|arg| arg == "--autostart")
-}
-
 fn main() -> eframe::Result<()> {
-    i18n::init();
-
-    let system_start = settings::load_setting().unwrap_or(false);
-    let background = is_background_mode();
-    let autostart_launch = is_autostart_launch();
-
-    let (tray_tx, tray_rx) = mpsc::channel();
-    tray::spawn_tray(tray_tx);
+    let args = cli::parse();
+    let settings = settings::load();
+    i18n::init(settings.language);
 
-    // Ensure registry matches the saved setting at startup.
-    if let Err(err) = autostart::set_enabled(system_start) {
-        debug_err!("failed to sync autostart on startup: {err}");
+    if let Some(command) = args.command {
+        // Scripted use: no window, no tray.
+        std::process::exit(cli::run(command, &settings));
     }
 
+    let system_start = settings.system_start;
+    let background = args.background || args.autostart;
+    let autostart_launch = args.autostart;
+
     if autostart_launch && !system_start {
-        // Launched from a stale autostart entry, but the setting is now off.
-        // Exit without showing UI.
+        // Launched from a stale autostart entry, but the setting is now off. Exit before
+        // taking the instance lock, socket or tray, and without showing UI.
         return Ok(());
     }
 
+    let instance_lock = match instance::acquire() {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            // Already running: bring up its window (a background launch has nothing to show)
+            // instead of starting a second tray icon and notification loop.
+            if !background && let Err(err) = instance::send(TrayCommand::Open) {
+                debug_err!("failed to reach the running instance: {err}");
+            }
+            return Ok(());
+        }
+        Err(err) => {
+            debug_err!("single-instance check failed: {err}");
+            None
+        }
+    };
+
+    let (tray_tx, tray_rx) = mpsc::channel();
+    if let Some(lock) = &instance_lock {
+        instance::listen(lock, tray_tx.clone());
+    }
+    tray::spawn_tray(tray_tx);
+
+    // The autostart entry is left as it is: overwriting it here would hide a moved EXE or an
+    // entry removed elsewhere. Settings shows any mismatch and offers to repair it.
+
     let mut viewport = egui::ViewportBuilder::default()
         .with_inner_size([500.0, 600.0])
         // Tray-first UX (like AV apps): keep the app out of the taskbar.
@@ -75,7 +102,8 @@ fn main() -> eframe::Result<()> {
         Box::new(move |cc| {
             tray::set_repaint_context(cc.egui_ctx.clone());
             Ok(Box::new(app::SilliReminder::new(
-                system_start,
+                &cc.egui_ctx,
+                settings,
                 background,
                 tray_rx,
             )))
@@ -0,0 +1,382 @@
+//! Quick add: a reminder typed as one line, e.g. `jutro 9:00 dentysta` or
+//! `next friday pay rent`.
+//!
+//! [`parse`] picks the first date and the first time phrase out of the line (in Polish or
+//! English, with or without Polish diacritics) and keeps the remaining words as the note.
+//!
+//! Dates:
+//! - `today`, `tomorrow`, `day after tomorrow` / `dziś`, `dzisiaj`, `jutro`, `pojutrze`
+//! - `in 3 days`, `in a week`, `in 2 months`, `in 1 year` / `za 3 dni`, `za tydzień`,
+//!   `za 2 miesiące`, `za rok`
+//! - weekdays, optionally with `on` / `next` / `w` / `we` / `w przyszły`: the first such day
+//!   after today (`friday`, `next monday`, `w piątek`, `we wtorek`, `w przyszłą środę`)
+//! - `next week`, `next month` / `w przyszłym tygodniu`, `w przyszłym miesiącu`
+//! - `2026-11-03`, `3.11.2026`, `3.11` (the next 3 November)
+//!
+//! Times: `14:00`, `9:30`, `9am`, `9:30 pm`, `at 9`, `o 9`, `o godz. 9`, `noon` / `w południe`,
+//! and `in 2 hours` / `za 30 minut` (which also set the date).
+//!
+//! A time without a date means today, or tomorrow once that time has passed.
+
+use chrono::{
+    Datelike as _, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike as _, Weekday,
+};
+
+/// A parsed quick-add line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickAdd {
+    /// `None` when the line has neither a date nor a time.
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+    /// The words that weren't part of the date or time.
+    pub note: String,
+}
+
+/// What a phrase at some position in the line means.
+enum Phrase {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+}
+
+/// Parses `input` relative to `now`.
+pub fn parse(input: &str, now: NaiveDateTime) -> QuickAdd {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let keys: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+    let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+
+    let mut date = None;
+    let mut time = None;
+    let mut note = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let rest = &keys[i..];
+        let found = match (date, time) {
+            (None, None) => match_date(rest, now.date()).or_else(|| match_time_phrase(rest, now)),
+            (None, Some(_)) => match_date(rest, now.date()),
+            (Some(_), None) => match_time(rest).map(|(n, t)| (n, Phrase::Time(t))),
+            (Some(_), Some(_)) => None,
+        };
+
+        match found {
+            Some((len, phrase)) => {
+                match phrase {
+                    Phrase::Date(d) => date = Some(d),
+                    Phrase::Time(t) => time = Some(t),
+                    Phrase::DateTime(dt) => {
+                        date = Some(dt.date());
+                        time = Some(dt.time());
+                    }
+                }
+                i += len;
+            }
+            None => {
+                note.push(words[i]);
+                i += 1;
+            }
+        }
+    }
+
+    if date.is_none()
+        && let Some(t) = time
+    {
+        let today = now.date();
+        date = if t > now.time() {
+            Some(today)
+        } else {
+            today.succ_opt()
+        };
+    }
+
+    QuickAdd {
+        date,
+        time,
+        note: note.join(" "),
+    }
+}
+
+/// Lowercase, without Polish diacritics and trailing punctuation, for matching.
+fn normalize(word: &str) -> String {
+    word.trim_end_matches(['.', ',', ':', ';', '!', '?'])
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'ą' => 'a',
+            'ć' => 'c',
+            'ę' => 'e',
+            'ł' => 'l',
+            'ń' => 'n',
+            'ó' => 'o',
+            'ś' => 's',
+            'ź' | 'ż' => 'z',
+            _ => c,
+        })
+        .collect()
+}
+
+/// A date phrase at the start of `words`: how many words it spans and the date.
+fn match_date(words: &[&str], today: NaiveDate) -> Option<(usize, Phrase)> {
+    let date = |len: usize, d: Option<NaiveDate>| d.map(|d| (len, Phrase::Date(d)));
+
+    match words {
+        ["today" | "dzis" | "dzisiaj", ..] => date(1, Some(today)),
+        ["tomorrow" | "jutro", ..] => date(1, today.succ_opt()),
+        ["pojutrze", ..] | ["day", "after", "tomorrow", ..] => {
+            let len = if words[0] == "pojutrze" { 1 } else { 3 };
+            date(len, today.checked_add_days(Days::new(2)))
+        }
+        ["next", "week", ..] | ["w", "przyszlym" | "nastepnym", "tygodniu", ..] => {
+            let len = if words[0] == "next" { 2 } else { 3 };
+            date(len, today.checked_add_days(Days::new(7)))
+        }
+        ["next", "month", ..] | ["w", "przyszlym" | "nastepnym", "miesiacu", ..] => {
+            let len = if words[0] == "next" { 2 } else { 3 };
+            date(len, today.checked_add_months(Months::new(1)))
+        }
+        ["in" | "za", ..] => {
+            let (n, unit_at) = match words.get(1) {
+                Some(&("a" | "an")) if words[0] == "in" => (1, 2),
+                Some(n) if n.parse::<u32>().is_ok() => (n.parse().ok()?, 2),
+                // `za tydzień`, `za miesiąc`, `za rok`
+                Some(_) if words[0] == "za" => (1, 1),
+                _ => return None,
+            };
+            let d = match *words.get(unit_at)? {
+                "day" | "days" | "dzien" | "dni" => today.checked_add_days(Days::new(n.into())),
+                "week" | "weeks" | "tydzien" | "tygodnie" | "tygodni" => {
+                    today.checked_add_days(Days::new(u64::from(n) * 7))
+                }
+                "month" | "months" | "miesiac" | "miesiace" | "miesiecy" => {
+                    today.checked_add_months(Months::new(n))
+                }
+                "year" | "years" | "rok" | "lata" | "lat" => {
+                    today.checked_add_months(Months::new(n.checked_mul(12)?))
+                }
+                _ => return None,
+            };
+            date(unit_at + 1, d)
+        }
+        _ => match_weekday(words, today).or_else(|| date(1, parse_numeric_date(words[0], today))),
+    }
+}
+
+/// `friday`, `on friday`, `next friday`, `w piątek`, `w przyszły piątek`, ...
+fn match_weekday(words: &[&str], today: NaiveDate) -> Option<(usize, Phrase)> {
+    let mut len = 0;
+    if matches!(words.first(), Some(&("on" | "next" | "this" | "w" | "we"))) {
+        len += 1;
+    }
+    if matches!(
+        words.get(len),
+        Some(&("przyszly" | "przyszla" | "nastepny" | "nastepna"))
+    ) {
+        len += 1;
+    }
+    let weekday = weekday(words.get(len)?)?;
+
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 { 7 } else { ahead };
+    let d = today.checked_add_days(Days::new(ahead.into()))?;
+    Some((len + 1, Phrase::Date(d)))
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" | "poniedzialek" => Weekday::Mon,
+        "tuesday" | "wtorek" => Weekday::Tue,
+        "wednesday" | "sroda" | "srode" => Weekday::Wed,
+        "thursday" | "czwartek" => Weekday::Thu,
+        "friday" | "piatek" => Weekday::Fri,
+        "saturday" | "sobota" | "sobote" => Weekday::Sat,
+        "sunday" | "niedziela" | "niedziele" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// `2026-11-03`, `3.11.2026` or `3.11` (the next 3 November, counting today).
+fn parse_numeric_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(d) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(d);
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(word, "%d.%m.%Y") {
+        return Some(d);
+    }
+
+    let (day, month) = word.trim_end_matches('.').split_once('.')?;
+    let (day, month): (u32, u32) = (day.parse().ok()?, month.parse().ok()?);
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(d) if d >= today => Some(d),
+        _ => NaiveDate::from_ymd_opt(today.year() + 1, month, day),
+    }
+}
+
+/// A time phrase, or `in 2 hours` / `za 30 minut`, which is a moment relative to `now`.
+fn match_time_phrase(words: &[&str], now: NaiveDateTime) -> Option<(usize, Phrase)> {
+    if let ["in" | "za", n, unit, ..] = words
+        && let Ok(n) = n.parse::<i64>()
+    {
+        let minutes = match *unit {
+            "hour" | "hours" | "godzine" | "godziny" | "godzin" => n.checked_mul(60)?,
+            "minute" | "minutes" | "min" | "minuty" | "minut" => n,
+            _ => return match_time(words).map(|(len, t)| (len, Phrase::Time(t))),
+        };
+        let at = now.checked_add_signed(chrono::Duration::try_minutes(minutes)?)?;
+        // Drop the seconds so it reads like a time the user typed.
+        let at = at.with_second(0)?.with_nanosecond(0)?;
+        return Some((3, Phrase::DateTime(at)));
+    }
+
+    match_time(words).map(|(len, t)| (len, Phrase::Time(t)))
+}
+
+/// `14:00`, `9am`, `9:30 pm`, `at 9`, `o 9`, `o godz. 9`, `noon`, `w południe`.
+fn match_time(words: &[&str]) -> Option<(usize, NaiveTime)> {
+    match words {
+        ["noon" | "poludnie", ..] => return Some((1, NaiveTime::from_hms_opt(12, 0, 0)?)),
+        ["at", "noon", ..] | ["w", "poludnie", ..] => {
+            return Some((2, NaiveTime::from_hms_opt(12, 0, 0)?));
+        }
+        _ => {}
+    }
+
+    // `at` / `o` / `o godz.` allow a bare hour (`at 9`); without them a bare number is
+    // more likely part of the note.
+    let mut len = 0;
+    if matches!(words.first(), Some(&("at" | "o" | "godz" | "godz."))) {
+        len += 1;
+        if words[0] == "o" && matches!(words.get(1), Some(&("godz" | "godz." | "godzinie"))) {
+            len += 1;
+        }
+    }
+    let bare_hour_ok = len > 0;
+
+    let word = *words.get(len)?;
+    let (clock, suffix) = split_am_pm(word);
+    let suffix = match suffix {
+        Some(s) => Some(s),
+        None => match words.get(len + 1) {
+            Some(&s @ ("am" | "pm")) => {
+                len += 1;
+                Some(s)
+            }
+            _ => None,
+        },
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None if bare_hour_ok || suffix.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match suffix {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|t| (len + 1, t))
+}
+
+/// `9:30pm` -> (`9:30`, `pm`).
+fn split_am_pm(word: &str) -> (&str, Option<&'static str>) {
+    if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some("am"))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some("pm"))
+    } else {
+        (word, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday, 14 October 2026, 10:30:45.
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(10, 30, 45)
+            .unwrap()
+    }
+
+    /// `date` is (month, day) in 2026, `time` is (hour, minute).
+    fn check(input: &str, date: Option<(u32, u32)>, time: Option<(u32, u32)>, note: &str) {
+        let expected = QuickAdd {
+            date: date.map(|(m, d)| NaiveDate::from_ymd_opt(2026, m, d).unwrap()),
+            time: time.map(|(h, m)| NaiveTime::from_hms_opt(h, m, 0).unwrap()),
+            note: note.to_owned(),
+        };
+        assert_eq!(parse(input, now()), expected, "{input:?}");
+    }
+
+    #[test]
+    fn polish() {
+        check("jutro dentysta", Some((10, 15)), None, "dentysta");
+        check("pojutrze zakupy", Some((10, 16)), None, "zakupy");
+        check(
+            "za 3 dni przegląd auta",
+            Some((10, 17)),
+            None,
+            "przegląd auta",
+        );
+        check("w piątek basen", Some((10, 16)), None, "basen");
+        check(
+            "w piatek 14:00 basen",
+            Some((10, 16)),
+            Some((14, 0)),
+            "basen",
+        );
+        // 9:00 has passed today, so it is tomorrow's.
+        check("lekarz o godz. 9", Some((10, 15)), Some((9, 0)), "lekarz");
+        check("o godz. 11 lekarz", Some((10, 14)), Some((11, 0)), "lekarz");
+    }
+
+    #[test]
+    fn english() {
+        check("tomorrow call mom", Some((10, 15)), None, "call mom");
+        check(
+            "in 2 weeks renew passport",
+            Some((10, 28)),
+            None,
+            "renew passport",
+        );
+        check(
+            "next monday 14:00 standup",
+            Some((10, 19)),
+            Some((14, 0)),
+            "standup",
+        );
+        check(
+            "take pills 9pm",
+            Some((10, 14)),
+            Some((21, 0)),
+            "take pills",
+        );
+        check(
+            "in 2 hours check the oven",
+            Some((10, 14)),
+            Some((12, 30)),
+            "check the oven",
+        );
+    }
+
+    #[test]
+    fn no_date_or_time() {
+        check("buy milk", None, None, "buy milk");
+        check("room 101 at the back", None, None, "room 101 at the back");
+        check("", None, None, "");
+    }
+
+    #[test]
+    fn trailing_punctuation() {
+        check("dentysta jutro.", Some((10, 15)), None, "dentysta");
+        check("pay rent tomorrow.", Some((10, 15)), None, "pay rent");
+        check("Tomorrow, 9am: gym", Some((10, 15)), Some((9, 0)), "gym");
+        check("urodziny 3.11.", Some((11, 3)), None, "urodziny");
+    }
+}
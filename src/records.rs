@@ -0,0 +1,362 @@
+//! CSV and JSON export/import: one flat [`Record`] per reminder, for spreadsheets and reports.
+//!
+//! The columns are stable; new ones are only ever added. On import every column except
+//! `date` and `note` may be missing or empty, so a spreadsheet with just those two is enough:
+//!
+//! - `id`: informational; imported reminders get new ids.
+//! - `uid`: identifies the reminder across files (see [`crate::ical`]); rows whose `uid` is
+//!   already in the database are skipped.
+//! - `date` (`YYYY-MM-DD`), `time` (`HH:MM`), `note`, `recurrence` (an RRULE).
+//! - `alerts`: lead times such as `7d, 3d, 1d`; `custom_alerts` says whether they are the
+//!   reminder's own (otherwise the defaults apply). Missing, it is true when `alerts` is set.
+//! - `alerts_fired`: the lead times already announced (what the old `notified_level` held).
+//! - `snoozed_until`, `completed_at`: `YYYY-MM-DD HH:MM`.
+//! - `priority`: `low`, `normal` (when missing), `high` or `critical`.
+//! - `tags`: tag names such as `work, home` (a comma inside a name is written `\,`). Tags
+//!   the database doesn't have yet are created on import.
+//!
+//! CSV files may use `,` or `;` (as spreadsheets set to Polish or German write them).
+
+use std::error::Error;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::alerts;
+use crate::db_operations::{Priority, Reminder};
+use crate::recurrence::Recurrence;
+use crate::transfer::Item;
+
+const DATE: &str = "%Y-%m-%d";
+const TIME: &str = "%H:%M";
+const DATE_TIME: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    pub id: Option<i64>,
+    pub uid: Option<String>,
+    pub date: String,
+    pub time: Option<String>,
+    pub note: String,
+    pub recurrence: Option<String>,
+    pub custom_alerts: Option<bool>,
+    pub alerts: Option<String>,
+    pub alerts_fired: Option<String>,
+    pub snoozed_until: Option<String>,
+    pub completed_at: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Option<String>,
+}
+
+impl From<&Reminder> for Record {
+    fn from(r: &Reminder) -> Self {
+        let timed = r.time.is_some();
+        let offsets: Vec<i64> = r.alerts.iter().map(|a| a.offset_minutes).collect();
+        let fired: Vec<i64> = r
+            .alerts
+            .iter()
+            .filter(|a| a.fired_at.is_some())
+            .map(|a| a.offset_minutes)
+            .collect();
+
+        Self {
+            id: Some(r.id),
+            uid: r.uid.clone(),
+            date: r.date.format(DATE).to_string(),
+            time: r.time.map(|t| t.format(TIME).to_string()),
+            note: r.note.clone(),
+            recurrence: r.recurrence.as_ref().map(Recurrence::to_string),
+            custom_alerts: Some(r.custom_alerts),
+            alerts: Some(alerts::format_offsets(&alerts::user_offsets(
+                &offsets, timed,
+            ))),
+            alerts_fired: Some(alerts::format_offsets(&fired)),
+            snoozed_until: r.snoozed_until.map(|t| t.format(DATE_TIME).to_string()),
+            completed_at: r.completed_at.map(|t| t.format(DATE_TIME).to_string()),
+            priority: Some(r.priority.name().to_owned()),
+            tags: Some(format_tags(r.tags.iter().map(|t| t.name.as_str()))),
+        }
+    }
+}
+
+impl TryFrom<Record> for Item {
+    type Error = String;
+
+    fn try_from(r: Record) -> Result<Self, Self::Error> {
+        let date = NaiveDate::parse_from_str(r.date.trim(), DATE)
+            .map_err(|_| format!("invalid date {:?} (expected YYYY-MM-DD)", r.date))?;
+        let time = non_empty(&r.time)
+            .map(|t| {
+                NaiveTime::parse_from_str(t, TIME)
+                    .map_err(|_| format!("invalid time {t:?} (expected HH:MM)"))
+            })
+            .transpose()?;
+
+        let note = r.note.trim();
+        if note.is_empty() {
+            return Err("note is empty".to_owned());
+        }
+
+        let recurrence = non_empty(&r.recurrence)
+            .map(|rule| {
+                rule.parse::<Recurrence>()
+                    .map_err(|err| format!("invalid recurrence {rule:?}: {err}"))
+            })
+            .transpose()?;
+
+        let priority = match non_empty(&r.priority) {
+            Some(p) => Priority::from_name(p).ok_or_else(|| {
+                format!("invalid priority {p:?} (expected low, normal, high or critical)")
+            })?,
+            None => Priority::default(),
+        };
+
+        let offsets = |column: &str, value: &Option<String>| {
+            non_empty(value)
+                .map(|v| alerts::parse_offsets(v).map_err(|err| format!("invalid {column}: {err}")))
+                .transpose()
+        };
+        let own_alerts = offsets("alerts", &r.alerts)?;
+        let fired = offsets("alerts_fired", &r.alerts_fired)?.unwrap_or_default();
+        let alerts = match r.custom_alerts {
+            Some(true) => Some(own_alerts.unwrap_or_default()),
+            Some(false) => None,
+            None => own_alerts,
+        };
+
+        Ok(Item {
+            uid: non_empty(&r.uid).map(str::to_owned),
+            date,
+            time,
+            note: note.to_owned(),
+            recurrence,
+            unsupported_rule: None,
+            priority,
+            tags: non_empty(&r.tags).map(parse_tags).unwrap_or_default(),
+            alerts,
+            alerts_fired: fired,
+            snoozed_until: parse_date_time("snoozed_until", &r.snoozed_until)?,
+            completed_at: parse_date_time("completed_at", &r.completed_at)?,
+        })
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Tag names joined with `, `, with a backslash before any `,` or `\` inside a name.
+fn format_tags<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    let escaped: Vec<String> = names
+        .into_iter()
+        .map(|name| name.replace('\\', "\\\\").replace(',', "\\,"))
+        .collect();
+    escaped.join(", ")
+}
+
+/// The reverse of [`format_tags`]; blank names are dropped.
+fn parse_tags(value: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut name = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => name.extend(chars.next()),
+            ',' => names.push(std::mem::take(&mut name)),
+            _ => name.push(c),
+        }
+    }
+    names.push(name);
+
+    names
+        .into_iter()
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn parse_date_time(column: &str, value: &Option<String>) -> Result<Option<NaiveDateTime>, String> {
+    non_empty(value)
+        .map(|v| {
+            NaiveDateTime::parse_from_str(v, DATE_TIME)
+                .map_err(|_| format!("invalid {column} {v:?} (expected YYYY-MM-DD HH:MM)"))
+        })
+        .transpose()
+}
+
+pub fn export_csv(reminders: &[Reminder]) -> Result<String, Box<dyn Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for r in reminders {
+        writer.serialize(Record::from(r))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+pub fn export_json(reminders: &[Reminder]) -> Result<String, Box<dyn Error>> {
+    let records: Vec<Record> = reminders.iter().map(Record::from).collect();
+    let mut json = serde_json::to_string_pretty(&records)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// The valid rows of a CSV file, and one message per invalid row (`line 3: ...`).
+pub fn parse_csv(text: &str) -> Result<(Vec<Item>, Vec<String>), csv::Error> {
+    let text = text.trim_start_matches('\u{feff}');
+    let header = text.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(';') && !header.contains(',') {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::Headers)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    // The reader counts a record as starting at the blank lines before it; skip those.
+    let line_at = |position: Option<&csv::Position>| {
+        let start = position.map_or(0, |p| p.byte() as usize).min(text.len());
+        let blank = text[start..].len() - text[start..].trim_start_matches(['\r', '\n']).len();
+        text[..start + blank].matches('\n').count() + 1
+    };
+
+    let headers = reader.headers()?.clone();
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+
+    for row in reader.records() {
+        let row = match row {
+            Ok(row) => row,
+            Err(err) => {
+                let line = line_at(err.position());
+                errors.push(format!("line {line}: {err}"));
+                continue;
+            }
+        };
+        let line = line_at(row.position());
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        let item = row
+            .deserialize::<Record>(Some(&headers))
+            .map_err(|err| err.to_string())
+            .and_then(Item::try_from);
+        match item {
+            Ok(item) => items.push(item),
+            Err(err) => errors.push(format!("line {line}: {err}")),
+        }
+    }
+    Ok((items, errors))
+}
+
+/// The valid entries of a JSON array, and one message per invalid entry (`entry 3: ...`,
+/// counting from 1).
+pub fn parse_json(text: &str) -> Result<(Vec<Item>, Vec<String>), serde_json::Error> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(text)?;
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (i, value) in values.into_iter().enumerate() {
+        let item = serde_json::from_value::<Record>(value)
+            .map_err(|err| err.to_string())
+            .and_then(Item::try_from);
+        match item {
+            Ok(item) => items.push(item),
+            Err(err) => errors.push(format!("entry {}: {err}", i + 1)),
+        }
+    }
+    Ok((items, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn reports_bad_csv_rows_by_line() {
+        let text = "\u{feff}date,time,note,priority\n\
+            2026-10-14,09:30,Dentist,high\n\
+            2026-13-01,,Bad date,\n\
+            \n\
+            2026-10-15,,  ,\n\
+            2026-10-16,9.30,Bad time,\n\
+            2026-10-17,,Bad priority,urgent\n\
+            2026-10-18,,Rent,\n";
+        let (items, errors) = parse_csv(text).unwrap();
+
+        let notes: Vec<&str> = items.iter().map(|i| i.note.as_str()).collect();
+        assert_eq!(notes, ["Dentist", "Rent"]);
+        assert_eq!(items[0].time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(items[0].priority, Priority::High);
+        assert_eq!(
+            errors,
+            [
+                r#"line 3: invalid date "2026-13-01" (expected YYYY-MM-DD)"#,
+                "line 5: note is empty",
+                r#"line 6: invalid time "9.30" (expected HH:MM)"#,
+                r#"line 7: invalid priority "urgent" (expected low, normal, high or critical)"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_bad_json_entries_by_position() {
+        let text = r#"[
+            {"date": "2026-10-14", "note": "Dentist", "alerts": "1d, 2h"},
+            {"date": "2026-10-15", "note": "Bad alerts", "alerts": "soon"},
+            {"date": "2026-10-16", "note": "Bad rule", "recurrence": "FREQ=MONTHLY;BYSETPOS=-1"},
+            42,
+            {"note": "No date"}
+        ]"#;
+        let (items, errors) = parse_json(text).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].alerts, Some(vec![alerts::DAY, 2 * alerts::HOUR]));
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        for (error, entry) in errors.iter().zip([2, 3, 4, 5]) {
+            assert!(error.starts_with(&format!("entry {entry}: ")), "{error}");
+        }
+        assert!(errors[0].contains("invalid alerts"), "{}", errors[0]);
+        assert!(errors[1].contains("invalid recurrence"), "{}", errors[1]);
+
+        assert!(parse_json("{\"date\": \"2026-10-14\"}").is_err());
+    }
+
+    #[test]
+    fn detects_the_delimiter() {
+        let semicolons = "date;note;tags\n2026-10-14;Pay rent, water;bills, home\n";
+        let (items, errors) = parse_csv(semicolons).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(items[0].date, date(2026, 10, 14));
+        assert_eq!(items[0].note, "Pay rent, water");
+        assert_eq!(items[0].tags, ["bills", "home"]);
+
+        let commas = "date,note\n2026-10-14,Pay rent; water\n";
+        let (items, errors) = parse_csv(commas).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(items[0].note, "Pay rent; water");
+    }
+
+    #[test]
+    fn escapes_tag_names() {
+        let names = ["work", "a, b", "back\\slash", "trailing\\"];
+        let formatted = format_tags(names);
+        assert_eq!(formatted, r"work, a\, b, back\\slash, trailing\\");
+        assert_eq!(parse_tags(&formatted), names);
+
+        assert_eq!(parse_tags(" work ,, home ,"), ["work", "home"]);
+        assert!(parse_tags("").is_empty());
+
+        let text = format!("date,note,tags\n2026-10-14,Rent,\"{formatted}\"\n");
+        let (items, _) = parse_csv(&text).unwrap();
+        assert_eq!(items[0].tags, names);
+    }
+}
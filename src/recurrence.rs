@@ -0,0 +1,486 @@
+//! Recurrence rules for repeating reminders.
+//!
+//! Rules are stored as a small RRULE subset (RFC 5545), e.g.
+//! `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20271231`.
+//!
+//! Supported parts:
+//! - `FREQ`: `DAILY`, `WEEKLY`, `MONTHLY`, `YEARLY`
+//! - `INTERVAL`: every N periods (default 1)
+//! - `BYDAY`: weekdays, for `DAILY`/`WEEKLY` (default: the start's weekday for `WEEKLY`)
+//! - `BYMONTHDAY`: days of month, for `MONTHLY`; `-1` is the last day (default: the start's day)
+//! - `UNTIL`: last allowed date (inclusive)
+//! - `COUNT`: occurrences left, *including* the current one
+//! - `WKST`: only `MO`
+//!
+//! Any other part, or `BYDAY`/`BYMONTHDAY` with a frequency not listed above, is rejected
+//! rather than ignored, so an imported rule never silently repeats on different days.
+//!
+//! A reminder only stores its current occurrence; the series is anchored at that date and
+//! rolled forward one occurrence at a time. That is why `COUNT` counts down instead of being
+//! relative to the original start. Dates that don't exist (Feb 30, Feb 29 in non-leap years)
+//! are skipped, as in RFC 5545.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike as _, Days, Months, NaiveDate, Weekday};
+
+/// Upper bound on periods scanned when looking for the next occurrence, so that
+/// a rule that can never match (e.g. `BYMONTHDAY=31` every 12 months from April)
+/// terminates.
+const MAX_PERIODS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [
+        Frequency::Daily,
+        Frequency::Weekly,
+        Frequency::Monthly,
+        Frequency::Yearly,
+    ];
+
+    fn as_rrule(self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_weekday: Vec<Weekday>,
+    pub by_month_day: Vec<i32>,
+    pub until: Option<NaiveDate>,
+    pub count: Option<u32>,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            by_weekday: Vec::new(),
+            by_month_day: Vec::new(),
+            until: None,
+            count: None,
+        }
+    }
+
+    /// First occurrence strictly after `after`, for a series whose current occurrence is
+    /// `anchor`. Ignores `COUNT` (see [`Recurrence::advance`]) but honours `UNTIL`.
+    pub fn next_after(&self, anchor: NaiveDate, after: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval.max(1);
+
+        for period in 0..MAX_PERIODS {
+            let step = period.checked_mul(interval)?;
+            let mut candidates = self.period_candidates(anchor, step)?;
+            candidates.sort();
+
+            if let Some(date) = candidates.into_iter().find(|d| *d >= anchor && *d > after) {
+                return match self.until {
+                    Some(until) if date > until => None,
+                    _ => Some(date),
+                };
+            }
+        }
+
+        None
+    }
+
+    /// Moves the series past `after`: returns the next occurrence and the rule to store with
+    /// it (`COUNT` decremented once per skipped occurrence), or `None` when the series ended.
+    pub fn advance(&self, anchor: NaiveDate, after: NaiveDate) -> Option<(NaiveDate, Recurrence)> {
+        let mut rule = self.clone();
+        let mut current = anchor;
+
+        loop {
+            if let Some(count) = rule.count {
+                if count <= 1 {
+                    return None;
+                }
+                rule.count = Some(count - 1);
+            }
+
+            current = rule.next_after(anchor, current)?;
+            if current > after {
+                return Some((current, rule));
+            }
+        }
+    }
+
+    /// Candidate dates of the period `step` periods after the one containing `anchor`.
+    fn period_candidates(&self, anchor: NaiveDate, step: u32) -> Option<Vec<NaiveDate>> {
+        let dates = match self.frequency {
+            Frequency::Daily => {
+                let date = anchor.checked_add_days(Days::new(step as u64))?;
+                if self.by_weekday.is_empty() || self.by_weekday.contains(&date.weekday()) {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let week_start = anchor
+                    .checked_sub_days(Days::new(anchor.weekday().num_days_from_monday() as u64))?
+                    .checked_add_days(Days::new(step as u64 * 7))?;
+                let weekdays = if self.by_weekday.is_empty() {
+                    vec![anchor.weekday()]
+                } else {
+                    self.by_weekday.clone()
+                };
+                weekdays
+                    .into_iter()
+                    .filter_map(|wd| {
+                        week_start.checked_add_days(Days::new(wd.num_days_from_monday() as u64))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month_start = anchor.with_day(1)?.checked_add_months(Months::new(step))?;
+                let days = if self.by_month_day.is_empty() {
+                    vec![anchor.day() as i32]
+                } else {
+                    self.by_month_day.clone()
+                };
+                days.into_iter()
+                    .filter_map(|day| resolve_month_day(month_start, day))
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = anchor.year().checked_add(step as i32)?;
+                NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+        Some(dates)
+    }
+}
+
+/// Resolves a `BYMONTHDAY` value (1..=31, or negative counting from the end) in the
+/// month starting at `month_start`.
+fn resolve_month_day(month_start: NaiveDate, day: i32) -> Option<NaiveDate> {
+    if day > 0 {
+        return month_start.with_day(day as u32);
+    }
+
+    let next_month = month_start.checked_add_months(Months::new(1))?;
+    let from_end = day.checked_neg()? as u64;
+    let date = next_month.checked_sub_days(Days::new(from_end))?;
+    (date.month() == month_start.month()).then_some(date)
+}
+
+fn weekday_to_rrule(wd: Weekday) -> &'static str {
+    match wd {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn weekday_from_rrule(s: &str) -> Option<Weekday> {
+    Some(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_rrule())?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_weekday.is_empty() {
+            let days: Vec<&str> = self
+                .by_weekday
+                .iter()
+                .map(|d| weekday_to_rrule(*d))
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecurrenceError(String);
+
+impl fmt::Display for ParseRecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid recurrence rule: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRecurrenceError {}
+
+impl FromStr for Recurrence {
+    type Err = ParseRecurrenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg: &str| ParseRecurrenceError(msg.to_owned());
+
+        let mut frequency = None;
+        let mut rule = Recurrence::new(Frequency::Daily);
+
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| err(part))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(err(part)),
+                    });
+                }
+                "INTERVAL" => {
+                    rule.interval = value.parse().map_err(|_| err(part))?;
+                    if rule.interval == 0 {
+                        return Err(err(part));
+                    }
+                }
+                "BYDAY" => {
+                    rule.by_weekday = value
+                        .split(',')
+                        .map(|d| weekday_from_rrule(&d.to_ascii_uppercase()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| err(part))?;
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .map(|d| {
+                            d.parse::<i32>()
+                                .ok()
+                                .filter(|d| (-31..=31).contains(d) && *d != 0)
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| err(part))?;
+                }
+                "UNTIL" => {
+                    // Accept both DATE and DATE-TIME forms; only the date matters here.
+                    let date = value.get(..8).ok_or_else(|| err(part))?;
+                    rule.until =
+                        Some(NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| err(part))?);
+                }
+                "COUNT" => {
+                    rule.count = Some(value.parse().map_err(|_| err(part))?);
+                }
+                // Weeks start on Monday here, which is also the RFC 5545 default.
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                // Anything else (BYSETPOS, BYMONTH, ...) would change which dates the rule
+                // matches, so reject it rather than repeat on the wrong days.
+                _ => return Err(err(part)),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| err("missing FREQ"))?;
+        let by_weekday_ok = matches!(rule.frequency, Frequency::Daily | Frequency::Weekly);
+        if !rule.by_weekday.is_empty() && !by_weekday_ok {
+            return Err(err("BYDAY is only supported for DAILY and WEEKLY"));
+        }
+        if !rule.by_month_day.is_empty() && rule.frequency != Frequency::Monthly {
+            return Err(err("BYMONTHDAY is only supported for MONTHLY"));
+        }
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn rule(s: &str) -> Recurrence {
+        s.parse().unwrap()
+    }
+
+    /// The series from `anchor` up to `to`, one [`Recurrence::advance`] at a time, the way
+    /// completing each occurrence rolls it forward.
+    fn series(rule: &Recurrence, anchor: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut out = Vec::new();
+        let mut current = Some((anchor, rule.clone()));
+        while let Some((date, rule)) = current.filter(|(date, _)| *date <= to) {
+            out.push(date);
+            current = rule.advance(date, date);
+        }
+        out
+    }
+
+    #[test]
+    fn monthly_skips_months_without_the_day() {
+        let jan31 = date(2026, 1, 31);
+        let monthly = rule("FREQ=MONTHLY");
+        assert_eq!(monthly.next_after(jan31, jan31), Some(date(2026, 3, 31)));
+        assert_eq!(
+            series(&monthly, jan31, date(2026, 6, 30)),
+            [date(2026, 1, 31), date(2026, 3, 31), date(2026, 5, 31)]
+        );
+
+        let last_day = rule("FREQ=MONTHLY;BYMONTHDAY=-1");
+        assert_eq!(last_day.next_after(jan31, jan31), Some(date(2026, 2, 28)));
+        assert_eq!(
+            last_day.next_after(jan31, date(2028, 2, 1)),
+            Some(date(2028, 2, 29))
+        );
+    }
+
+    #[test]
+    fn yearly_on_feb_29_waits_for_a_leap_year() {
+        let leap_day = date(2028, 2, 29);
+        let yearly = rule("FREQ=YEARLY");
+        assert_eq!(
+            yearly.next_after(leap_day, leap_day),
+            Some(date(2032, 2, 29))
+        );
+        assert_eq!(
+            series(&yearly, leap_day, date(2036, 12, 31)),
+            [date(2028, 2, 29), date(2032, 2, 29), date(2036, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn count_counts_down_to_the_last_occurrence() {
+        let start = date(2026, 10, 14);
+        let (second, rest) = rule("FREQ=DAILY;COUNT=3").advance(start, start).unwrap();
+        assert_eq!((second, rest.count), (date(2026, 10, 15), Some(2)));
+        let (third, rest) = rest.advance(second, second).unwrap();
+        assert_eq!((third, rest.count), (date(2026, 10, 16), Some(1)));
+        assert_eq!(rest.advance(third, third), None);
+
+        // Skipping ahead uses up one per skipped occurrence.
+        let (next, rest) = rule("FREQ=DAILY;COUNT=5")
+            .advance(start, date(2026, 10, 16))
+            .unwrap();
+        assert_eq!((next, rest.count), (date(2026, 10, 17), Some(2)));
+        assert_eq!(
+            rule("FREQ=DAILY;COUNT=3").advance(start, date(2026, 10, 20)),
+            None
+        );
+
+        let far = date(2027, 1, 1);
+        assert_eq!(series(&rule("FREQ=DAILY;COUNT=3"), start, far).len(), 3);
+        assert_eq!(series(&rule("FREQ=DAILY;COUNT=0"), start, far), [start]);
+        assert_eq!(rule("FREQ=DAILY;COUNT=1").advance(start, start), None);
+        assert_eq!(rule("FREQ=DAILY;COUNT=0").advance(start, start), None);
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        let start = date(2026, 10, 14);
+        let daily = rule("FREQ=DAILY;UNTIL=20261016");
+        assert_eq!(
+            daily.next_after(start, date(2026, 10, 15)),
+            Some(date(2026, 10, 16))
+        );
+        assert_eq!(daily.next_after(start, date(2026, 10, 16)), None);
+        assert_eq!(
+            series(&daily, start, date(2026, 12, 31)),
+            [date(2026, 10, 14), date(2026, 10, 15), date(2026, 10, 16)]
+        );
+
+        let date_time = rule("FREQ=DAILY;UNTIL=20261016T235959Z");
+        assert_eq!(date_time.until, Some(date(2026, 10, 16)));
+        assert_eq!(date_time.advance(start, date(2026, 10, 16)), None);
+    }
+
+    #[test]
+    fn weekly_interval_with_weekdays() {
+        // 2026-10-14 is a Wednesday; the Monday of its week is before the start.
+        let start = date(2026, 10, 14);
+        let biweekly = rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE");
+        assert_eq!(
+            series(&biweekly, start, date(2026, 11, 15)),
+            [
+                date(2026, 10, 14),
+                date(2026, 10, 26),
+                date(2026, 10, 28),
+                date(2026, 11, 9),
+                date(2026, 11, 11),
+            ]
+        );
+        let (next, _) = biweekly.advance(start, date(2026, 10, 26)).unwrap();
+        assert_eq!(next, date(2026, 10, 28));
+    }
+
+    #[test]
+    fn parses_and_prints_the_supported_subset() {
+        for s in [
+            "FREQ=DAILY",
+            "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20271231",
+            "FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=12",
+            "FREQ=YEARLY;INTERVAL=3",
+        ] {
+            assert_eq!(rule(s).to_string(), s);
+        }
+        assert_eq!(
+            rule("RRULE:freq=weekly;wkst=MO;byday=fr").to_string(),
+            "FREQ=WEEKLY;BYDAY=FR"
+        );
+    }
+
+    #[test]
+    fn rejects_parts_it_cannot_honour() {
+        for s in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=MONTHLY;BYDAY=MO;BYSETPOS=-1",
+            "FREQ=MONTHLY;BYDAY=MO",
+            "FREQ=MONTHLY;BYDAY=1MO",
+            "FREQ=YEARLY;BYDAY=MO",
+            "FREQ=YEARLY;BYMONTH=3",
+            "FREQ=YEARLY;BYMONTHDAY=1",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=WEEKLY;WKST=SU;BYDAY=MO",
+            "FREQ=DAILY;UNTIL=2026",
+            "FREQ=DAILY;COUNT=-1",
+            "FREQ=DAILY;X-NAME=1",
+        ] {
+            assert!(s.parse::<Recurrence>().is_err(), "{s:?} parsed");
+        }
+    }
+}
@@ -1,40 +1,336 @@
-use std::fs::OpenOptions;
-use std::io::prelude::*;
-use std::path::PathBuf;
+//! User preferences, stored as TOML in `settings.toml` in the app data directory.
+//!
+//! Every key has a default (`#[serde(default)]`), so files written by older versions load
+//! fine. Keys only newer versions know are kept and written back unchanged, so going back
+//! to an older version and forth again doesn't lose them. `version` records the layout the
+//! file was written with, for migrations that can't be expressed as a default.
+//!
+//! Before that, the only preference (starting with the system) lived in
+//! `settings.sillisettings` as `1`/`0`; it is migrated on first load and then removed.
 
-fn settings_path() -> PathBuf {
-    crate::paths::app_data_dir().join("settings.sillisettings")
+use std::error::Error;
+use std::path::Path;
+
+use chrono::NaiveTime;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::alerts;
+use crate::i18n::Language;
+use crate::{debug_err, debug_log};
+
+/// Layout version written by this build.
+pub const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Start with the system (see [`crate::autostart`]).
+    pub system_start: bool,
+    /// How autostart is set up on Linux.
+    pub autostart_method: AutostartMethod,
+    /// UI language; `None` follows the system locale.
+    pub language: Option<Language>,
+    pub theme: Theme,
+    /// Default alert lead times, in minutes before due (see [`crate::alerts`]).
+    pub alert_offsets: Vec<i64>,
+    /// Re-announce overdue reminders once per day until handled.
+    pub overdue_renag: bool,
+    /// Seconds between reminder checks while the window is open.
+    pub check_interval_secs: u64,
+    /// Seconds between reminder checks while hidden in the tray.
+    pub background_check_interval_secs: u64,
+    /// Notifications reached during these hours are held back until they end.
+    pub quiet_hours: Option<QuietHours>,
+    /// How notifications are delivered (see [`crate::sinks`]).
+    pub notifications: NotificationSettings,
+    /// How many automatic database backups to keep (see [`crate::db_operations::backup_on_startup`]).
+    pub backup_retention: usize,
+    /// Keys this version doesn't know.
+    #[serde(flatten)]
+    unknown: toml::Table,
 }
 
-pub fn load_setting() -> std::io::Result<bool> {
-    let path = settings_path();
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            system_start: false,
+            autostart_method: AutostartMethod::Xdg,
+            language: None,
+            theme: Theme::System,
+            alert_offsets: alerts::DEFAULT_OFFSETS.to_vec(),
+            overdue_renag: false,
+            check_interval_secs: 10,
+            background_check_interval_secs: 60,
+            quiet_hours: None,
+            notifications: NotificationSettings::default(),
+            backup_retention: 10,
+            unknown: toml::Table::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
 
-    if !path.exists() {
-        return Ok(false);
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+}
+
+/// `xdg`: an entry in `~/.config/autostart`, started by the desktop session.
+/// `systemd`: a `systemctl --user` service bound to `graphical-session.target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutostartMethod {
+    Xdg,
+    Systemd,
+}
+
+/// Which notification sinks are enabled. Platform-specific ones are ignored elsewhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Balloon from the tray icon (Windows).
+    pub tray_balloon: bool,
+    /// Pop-up that stays until dismissed and offers snoozing (Windows).
+    pub message_box: bool,
+    /// Notification through the desktop's notification daemon (Linux).
+    pub desktop: bool,
+    /// Append every notification to `notifications.log` in the app data directory.
+    pub log_file: bool,
+    /// POST every notification as JSON to this URL.
+    pub webhook_url: Option<String>,
+    /// Run this shell command for every notification, with the details in `SILLI_*`
+    /// environment variables.
+    pub command: Option<String>,
+    /// Keys this version doesn't know.
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            tray_balloon: true,
+            message_box: true,
+            desktop: true,
+            log_file: false,
+            webhook_url: None,
+            command: None,
+            unknown: toml::Table::new(),
+        }
     }
+}
 
-    let content = std::fs::read_to_string(path)?;
-    let value = match content.trim() {
-        "1" | "true" | "True" | "TRUE" => true,
-        _ => false,
-    };
-    Ok(value)
+/// A daily time window, e.g. `22:00`-`07:00`; it may wrap past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    #[serde(with = "hh_mm")]
+    pub start: NaiveTime,
+    #[serde(with = "hh_mm")]
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
 }
 
-pub fn save_setting(system_start: bool) -> std::io::Result<()> {
-    let path = settings_path();
+/// `NaiveTime` as `"HH:MM"`, the same form the database uses.
+mod hh_mm {
+    use chrono::NaiveTime;
+    use serde::{Deserialize as _, Deserializer, Serializer, de::Error as _};
+
+    pub fn serialize<S: Serializer>(time: &NaiveTime, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&time.format("%H:%M").to_string())
+    }
 
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveTime, D::Error> {
+        let s = String::deserialize(d)?;
+        NaiveTime::parse_from_str(&s, "%H:%M").map_err(D::Error::custom)
     }
+}
 
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(path)?;
+const SETTINGS_FILE: &str = "settings.toml";
+/// Written before `settings.toml` existed: `1`/`0` for [`Settings::system_start`].
+const LEGACY_FILE: &str = "settings.sillisettings";
 
-    let value = if system_start { b"1" } else { b"0" };
-    file.write_all(value)?;
+/// Loads the settings, migrating the legacy file on first run. Never fails: an unreadable
+/// file falls back to defaults (and is left untouched until the next save).
+pub fn load() -> Settings {
+    load_from(&crate::paths::app_data_dir())
+}
+
+pub fn save(settings: &Settings) -> Result<(), Box<dyn Error>> {
+    save_to(&crate::paths::app_data_dir(), settings)
+}
+
+fn load_from(dir: &Path) -> Settings {
+    let path = dir.join(SETTINGS_FILE);
+    if path.exists() {
+        return match read(&path) {
+            Ok(settings) => settings,
+            Err(err) => {
+                debug_err!("failed to read {}: {err}", path.display());
+                Settings::default()
+            }
+        };
+    }
+
+    let legacy = dir.join(LEGACY_FILE);
+    if !legacy.exists() {
+        return Settings::default();
+    }
+    let settings = Settings {
+        system_start: load_legacy_flag(&legacy),
+        ..Settings::default()
+    };
+
+    debug_log!("migrating legacy settings -> {}", path.display());
+    match save_to(dir, &settings) {
+        Ok(()) => {
+            let _ = std::fs::remove_file(legacy);
+        }
+        Err(err) => debug_err!("failed to migrate settings: {err}"),
+    }
+    settings
+}
+
+fn read(path: &Path) -> Result<Settings, Box<dyn Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let settings: Settings = toml::from_str(&content)?;
+    if settings.version > SETTINGS_VERSION {
+        debug_log!(
+            "settings written by a newer version ({}); unknown keys are kept as they are",
+            settings.version
+        );
+    }
+    Ok(settings)
+}
+
+fn save_to(dir: &Path, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut settings = settings.clone();
+    settings.version = settings.version.max(SETTINGS_VERSION);
+
+    // Write next to the target and rename, so a crash never leaves a half-written file.
+    let path = dir.join(SETTINGS_FILE);
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, toml::to_string_pretty(&settings)?)?;
+    std::fs::rename(&tmp, &path)?;
     Ok(())
 }
+
+fn load_legacy_flag(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .is_ok_and(|content| matches!(content.trim(), "1" | "true" | "True" | "TRUE"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// A new, empty directory for one test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "silli_reminder-settings-{}-{name}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrates_the_legacy_file() {
+        for (content, system_start) in [("1\n", true), ("True", true), ("0", false), ("", false)] {
+            let dir = temp_dir("legacy");
+            std::fs::write(dir.join(LEGACY_FILE), content).unwrap();
+
+            let settings = load_from(&dir);
+            assert_eq!(settings.system_start, system_start, "{content:?}");
+            assert_eq!(
+                Settings {
+                    system_start: false,
+                    ..settings.clone()
+                },
+                Settings::default()
+            );
+            assert!(!dir.join(LEGACY_FILE).exists());
+            assert_eq!(read(&dir.join(SETTINGS_FILE)).unwrap(), settings);
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn starts_with_defaults() {
+        let dir = temp_dir("defaults");
+        assert_eq!(load_from(&dir), Settings::default());
+        assert!(!dir.join(SETTINGS_FILE).exists());
+
+        // A damaged file is left for the user to look at until the next save.
+        std::fs::write(dir.join(SETTINGS_FILE), "theme = [").unwrap();
+        std::fs::write(dir.join(LEGACY_FILE), "1").unwrap();
+        assert_eq!(load_from(&dir), Settings::default());
+        assert_eq!(
+            std::fs::read_to_string(dir.join(SETTINGS_FILE)).unwrap(),
+            "theme = ["
+        );
+        assert!(dir.join(LEGACY_FILE).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_keys_from_newer_versions() {
+        let dir = temp_dir("unknown");
+        std::fs::write(
+            dir.join(SETTINGS_FILE),
+            "version = 7\n\
+             theme = \"dark\"\n\
+             future_flag = true\n\
+             [notifications]\n\
+             log_file = true\n\
+             future_sink = \"x\"\n\
+             [future_table]\n\
+             answer = 42\n",
+        )
+        .unwrap();
+
+        let mut settings = load_from(&dir);
+        assert_eq!(settings.version, 7);
+        assert_eq!(settings.theme, Theme::Dark);
+        assert!(settings.notifications.log_file);
+
+        settings.quiet_hours = Some(QuietHours {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        });
+        save_to(&dir, &settings).unwrap();
+
+        let saved: toml::Table = std::fs::read_to_string(dir.join(SETTINGS_FILE))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(saved["version"].as_integer(), Some(7));
+        assert_eq!(saved["future_flag"].as_bool(), Some(true));
+        assert_eq!(saved["notifications"]["future_sink"].as_str(), Some("x"));
+        assert_eq!(saved["future_table"]["answer"].as_integer(), Some(42));
+        assert_eq!(saved["quiet_hours"]["start"].as_str(), Some("22:00"));
+        assert_eq!(load_from(&dir), settings);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,351 @@
+//! Notification delivery. Each [`NotificationSink`] is one way of telling the user (a tray
+//! balloon, a pop-up, a log line, a webhook, ...); [`Sinks`] holds the ones enabled in
+//! [`NotificationSettings`] and hands every notification to each of them.
+//!
+//! The reminder's [`Priority`] changes how loud that is: a critical one always opens the
+//! pop-up (even when it is turned off), a low one never does.
+
+use std::error::Error;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_derive::Serialize;
+
+use crate::db_operations::Priority;
+use crate::settings::NotificationSettings;
+use crate::tray::{self, TrayNotificationKind};
+use crate::{debug_err, paths};
+
+/// One notification about a reminder, already formatted for display.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub reminder_id: i64,
+    pub title: String,
+    pub body: String,
+    pub kind: TrayNotificationKind,
+    pub priority: Priority,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub note: String,
+}
+
+impl Notification {
+    fn kind_name(&self) -> &'static str {
+        match self.kind {
+            TrayNotificationKind::Quiet | TrayNotificationKind::Info => "info",
+            TrayNotificationKind::Warning => "warning",
+            TrayNotificationKind::Error => "error",
+        }
+    }
+}
+
+pub trait NotificationSink {
+    /// Short name for the debug log.
+    fn name(&self) -> &'static str;
+
+    /// Delivers `n`. Called on the UI thread: anything slow must move to its own thread.
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>>;
+}
+
+/// The enabled sinks, in registration order.
+pub struct Sinks(Vec<Box<dyn NotificationSink>>);
+
+impl Sinks {
+    pub fn from_settings(settings: &NotificationSettings) -> Self {
+        let mut sinks: Vec<Box<dyn NotificationSink>> = Vec::new();
+
+        if cfg!(target_os = "windows") {
+            if settings.tray_balloon {
+                sinks.push(Box::new(TrayBalloon));
+            }
+            // Registered anyway: critical reminders use it regardless of the setting.
+            sinks.push(Box::new(MessageBox {
+                enabled: settings.message_box,
+            }));
+        }
+        if cfg!(target_os = "linux") && settings.desktop {
+            sinks.push(Box::new(Desktop));
+        }
+        if settings.log_file {
+            sinks.push(Box::new(LogFile {
+                path: paths::app_data_dir().join("notifications.log"),
+            }));
+        }
+        if let Some(url) = non_empty(&settings.webhook_url) {
+            sinks.push(Box::new(Webhook {
+                url: url.to_owned(),
+            }));
+        }
+        if let Some(command) = non_empty(&settings.command) {
+            sinks.push(Box::new(CommandHook {
+                command: command.to_owned(),
+            }));
+        }
+
+        Self(sinks)
+    }
+
+    pub fn deliver(&self, n: &Notification) {
+        for sink in &self.0 {
+            if let Err(err) = sink.deliver(n) {
+                debug_err!("{} failed to deliver a notification: {err}", sink.name());
+            }
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+struct TrayBalloon;
+
+impl NotificationSink for TrayBalloon {
+    fn name(&self) -> &'static str {
+        "tray balloon"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        tray::show_balloon(&n.title, &n.body, n.kind);
+        Ok(())
+    }
+}
+
+/// Shown for every notification but low-priority ones while `enabled`, and for critical
+/// ones always.
+struct MessageBox {
+    enabled: bool,
+}
+
+impl MessageBox {
+    fn shows(&self, priority: Priority) -> bool {
+        match priority {
+            Priority::Low => false,
+            Priority::Normal | Priority::High => self.enabled,
+            Priority::Critical => true,
+        }
+    }
+}
+
+impl NotificationSink for MessageBox {
+    fn name(&self) -> &'static str {
+        "message box"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        if !self.shows(n.priority) {
+            return Ok(());
+        }
+        tray::show_message_box(&n.title, &n.body, n.kind, Some(n.reminder_id));
+        Ok(())
+    }
+}
+
+struct Desktop;
+
+impl NotificationSink for Desktop {
+    fn name(&self) -> &'static str {
+        "desktop notification"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        tray::show_desktop_notification(&n.title, &n.body, n.kind, Some(n.reminder_id));
+        Ok(())
+    }
+}
+
+/// One line per notification: `2026-11-03 09:00:00 [warning] #12 Title: body`, with the
+/// priority after the kind unless it is normal (`[error, critical]`).
+struct LogFile {
+    path: PathBuf,
+}
+
+impl LogFile {
+    /// The line for `n`, without the newline.
+    fn line(n: &Notification, now: NaiveDateTime) -> String {
+        let priority = match n.priority {
+            Priority::Normal => String::new(),
+            other => format!(", {}", other.name()),
+        };
+        format!(
+            "{} [{}{priority}] #{} {}: {}",
+            now.format("%Y-%m-%d %H:%M:%S"),
+            n.kind_name(),
+            n.reminder_id,
+            n.title,
+            n.body.replace('\n', " | ")
+        )
+    }
+}
+
+impl NotificationSink for LogFile {
+    fn name(&self) -> &'static str {
+        "log file"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", Self::line(n, Local::now().naive_local()))?;
+        Ok(())
+    }
+}
+
+/// POSTs a JSON document (see [`WebhookPayload`]) to `url`.
+struct Webhook {
+    url: String,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    reminder_id: i64,
+    kind: &'static str,
+    priority: &'static str,
+    title: &'a str,
+    body: &'a str,
+    note: &'a str,
+    date: String,
+    time: Option<String>,
+}
+
+impl NotificationSink for Webhook {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        let payload = serde_json::to_string(&WebhookPayload {
+            reminder_id: n.reminder_id,
+            kind: n.kind_name(),
+            priority: n.priority.name(),
+            title: &n.title,
+            body: &n.body,
+            note: &n.note,
+            date: n.date.to_string(),
+            time: n.time.map(|t| t.format("%H:%M").to_string()),
+        })?;
+
+        let url = self.url.clone();
+        std::thread::spawn(move || {
+            let result = ureq::post(&url)
+                .header("Content-Type", "application/json")
+                .send(payload.as_str());
+            if let Err(err) = result {
+                debug_err!("webhook {url} failed: {err}");
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Runs `command` through the system shell with the notification in the environment:
+/// `SILLI_REMINDER_ID`, `SILLI_KIND`, `SILLI_PRIORITY`, `SILLI_TITLE`, `SILLI_BODY`,
+/// `SILLI_NOTE`, `SILLI_DATE` and `SILLI_TIME` (empty for reminders without a time).
+struct CommandHook {
+    command: String,
+}
+
+impl NotificationSink for CommandHook {
+    fn name(&self) -> &'static str {
+        "command hook"
+    }
+
+    fn deliver(&self, n: &Notification) -> Result<(), Box<dyn Error>> {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(&self.command);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(&self.command);
+            cmd
+        };
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt as _;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let mut child = cmd
+            .env("SILLI_REMINDER_ID", n.reminder_id.to_string())
+            .env("SILLI_KIND", n.kind_name())
+            .env("SILLI_PRIORITY", n.priority.name())
+            .env("SILLI_TITLE", &n.title)
+            .env("SILLI_BODY", &n.body)
+            .env("SILLI_NOTE", &n.note)
+            .env("SILLI_DATE", n.date.to_string())
+            .env(
+                "SILLI_TIME",
+                n.time
+                    .map(|t| t.format("%H:%M").to_string())
+                    .unwrap_or_default(),
+            )
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // Reap it in the background so it doesn't linger as a zombie.
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(kind: TrayNotificationKind, priority: Priority) -> Notification {
+        Notification {
+            reminder_id: 12,
+            title: "Dentist".into(),
+            body: "Today at 09:00\nBring the card".into(),
+            kind,
+            priority,
+            date: NaiveDate::from_ymd_opt(2026, 11, 3).unwrap(),
+            time: NaiveTime::from_hms_opt(9, 0, 0),
+            note: String::new(),
+        }
+    }
+
+    #[test]
+    fn formats_log_lines() {
+        let now = NaiveDate::from_ymd_opt(2026, 11, 3)
+            .unwrap()
+            .and_hms_opt(8, 45, 7)
+            .unwrap();
+        assert_eq!(
+            LogFile::line(
+                &notification(TrayNotificationKind::Warning, Priority::Normal),
+                now
+            ),
+            "2026-11-03 08:45:07 [warning] #12 Dentist: Today at 09:00 | Bring the card"
+        );
+        assert_eq!(
+            LogFile::line(
+                &notification(TrayNotificationKind::Quiet, Priority::Critical),
+                now
+            ),
+            "2026-11-03 08:45:07 [info, critical] #12 Dentist: Today at 09:00 | Bring the card"
+        );
+    }
+
+    #[test]
+    fn message_box_follows_priority() {
+        for enabled in [false, true] {
+            let sink = MessageBox { enabled };
+            assert!(!sink.shows(Priority::Low));
+            assert_eq!(sink.shows(Priority::Normal), enabled);
+            assert_eq!(sink.shows(Priority::High), enabled);
+            assert!(sink.shows(Priority::Critical));
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Moving reminders to and from other apps: which reminders an export includes, and how
+//! imported entries become reminders. The file formats themselves live in [`ical`] and
+//! [`records`].
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::Connection;
+
+use crate::alerts;
+use crate::db_operations::{self, Alert, Priority, Reminder, TAG_COLORS, Tag};
+use crate::recurrence::Recurrence;
+use crate::settings::Settings;
+use crate::{ical, records};
+
+/// A file format reminders can be exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// iCalendar; the component is only used for exports.
+    Ical(ical::Component),
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// Guessed from the file extension (`.ics`, `.csv`, `.json`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "ics" | "ical" | "ifb" => Some(Self::Ical(ical::Component::Event)),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// One reminder read from a file, before it is checked against the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub uid: Option<String>,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub note: String,
+    pub recurrence: Option<Recurrence>,
+    /// The repeat rule as written in the file, when [`Recurrence`] can't represent it.
+    pub unsupported_rule: Option<String>,
+    pub priority: Priority,
+    /// Tag names; tags the database doesn't have yet are created on import.
+    pub tags: Vec<String>,
+    /// Own alert lead times in minutes; `None` means the defaults.
+    pub alerts: Option<Vec<i64>>,
+    /// Lead times whose notification already went out.
+    pub alerts_fired: Vec<i64>,
+    pub snoozed_until: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+/// Which reminders to export.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Completed reminders only.
+    pub done: bool,
+    /// Active and completed reminders.
+    pub all: bool,
+    /// Due on or after this date.
+    pub from: Option<NaiveDate>,
+    /// Due on or before this date.
+    pub to: Option<NaiveDate>,
+}
+
+/// The reminders `filter` selects, ordered by date/time.
+pub fn select(conn: &Connection, filter: &Filter) -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let mut reminders = Vec::new();
+    if filter.all || !filter.done {
+        reminders.extend(db_operations::list_reminders(conn)?);
+    }
+    if filter.all || filter.done {
+        reminders.extend(db_operations::list_completed_reminders(conn)?);
+    }
+
+    reminders.retain(|r| {
+        filter.from.is_none_or(|from| r.date >= from) && filter.to.is_none_or(|to| r.date <= to)
+    });
+    reminders.sort_by_key(|r| (r.date, r.time, r.id));
+    Ok(reminders)
+}
+
+/// The reminders in `filter` as a `format` file, and how many there are.
+pub fn export(
+    conn: &Connection,
+    filter: &Filter,
+    format: Format,
+) -> Result<(String, usize), Box<dyn Error>> {
+    let reminders = select(conn, filter)?;
+    let text = match format {
+        Format::Ical(component) => ical::export(&reminders, component),
+        Format::Csv => records::export_csv(&reminders)?,
+        Format::Json => records::export_json(&reminders)?,
+    };
+    Ok((text, reminders.len()))
+}
+
+/// A file read for import.
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub items: Vec<Item>,
+    /// One message per entry that couldn't be read (`line 3: invalid date ...`); the rest
+    /// can still be imported.
+    pub errors: Vec<String>,
+}
+
+/// Reads `text` as a `format` file. Only a file that can't be read at all is an error.
+pub fn parse(text: &str, format: Format) -> Result<Parsed, Box<dyn Error>> {
+    let (items, errors) = match format {
+        Format::Ical(_) => (ical::parse(text)?, Vec::new()),
+        Format::Csv => records::parse_csv(text)?,
+        Format::Json => records::parse_json(text)?,
+    };
+    Ok(Parsed { items, errors })
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Import entries that are already over at their own dates. Otherwise past one-off
+    /// entries are skipped and recurring ones start at their next occurrence from today
+    /// (completed entries are always kept, as history).
+    pub keep_past: bool,
+    /// Only report what would be imported.
+    pub dry_run: bool,
+}
+
+/// What an import did (or, for a dry run, would do).
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Already in the database (same `UID`).
+    pub duplicates: usize,
+    /// One-off entries that were already over.
+    pub past: usize,
+    /// Imported without repeating, because the rule isn't supported.
+    pub unsupported_rules: Vec<String>,
+}
+
+/// Adds `items` to the database in one transaction.
+///
+/// Entries whose `UID` is already known are skipped, so importing the same file twice is
+/// harmless. A dry run goes through the same steps and rolls them back.
+pub fn import(
+    conn: &Connection,
+    items: Vec<Item>,
+    settings: &Settings,
+    options: ImportOptions,
+) -> Result<ImportReport, Box<dyn Error>> {
+    let now = Local::now().naive_local();
+    let today = now.date();
+    let mut report = ImportReport::default();
+
+    let tx = conn.unchecked_transaction()?;
+    let mut tags = db_operations::list_tags(&tx)?;
+    for mut item in items {
+        if let Some(uid) = &item.uid
+            && db_operations::find_reminder_by_uid(&tx, uid)?.is_some()
+        {
+            report.duplicates += 1;
+            continue;
+        }
+
+        if !options.keep_past && item.completed_at.is_none() && item.date < today {
+            let next = item.recurrence.as_ref().and_then(|rule| {
+                today
+                    .pred_opt()
+                    .and_then(|yesterday| rule.advance(item.date, yesterday))
+            });
+            let Some((next_date, next_rule)) = next else {
+                report.past += 1;
+                continue;
+            };
+            item.date = next_date;
+            item.recurrence = Some(next_rule);
+        }
+
+        let tag_ids = tag_ids(&tx, &mut tags, &item.tags)?;
+        let custom_alerts = item.alerts.is_some();
+        let base: Vec<i64> = match &item.alerts {
+            Some(offsets) => offsets
+                .iter()
+                .copied()
+                .filter(|o| *o <= alerts::MAX_OFFSET)
+                .collect(),
+            None => alerts::default_offsets(
+                tags.iter().filter(|t| tag_ids.contains(&t.id)),
+                &settings.alert_offsets,
+            ),
+        };
+        let alerts: Vec<Alert> = alerts::effective_offsets(&base, item.time.is_some())
+            .into_iter()
+            .map(|offset_minutes| Alert {
+                offset_minutes,
+                fired_at: item.alerts_fired.contains(&offset_minutes).then_some(now),
+            })
+            .collect();
+
+        let id = db_operations::insert_reminder(
+            &tx,
+            item.date,
+            item.time,
+            &item.note,
+            item.recurrence.as_ref(),
+            custom_alerts,
+            &alerts,
+        )?;
+        if let Some(uid) = &item.uid {
+            db_operations::set_reminder_uid(&tx, id, uid)?;
+        }
+        if item.priority != Priority::Normal {
+            db_operations::set_reminder_priority(&tx, id, item.priority)?;
+        }
+        if !tag_ids.is_empty() {
+            db_operations::set_reminder_tags(&tx, id, &tag_ids)?;
+        }
+        if item.snoozed_until.is_some() {
+            db_operations::set_reminder_snoozed(&tx, id, item.snoozed_until)?;
+        }
+        if item.completed_at.is_some() {
+            db_operations::set_reminder_completed(&tx, id, item.completed_at)?;
+        }
+
+        report.imported += 1;
+        if let Some(rule) = item.unsupported_rule {
+            report.unsupported_rules.push(rule);
+        }
+    }
+
+    if !options.dry_run {
+        tx.commit()?;
+    }
+    Ok(report)
+}
+
+/// The ids of the tags called `names` (ignoring ASCII case, like the database does),
+/// creating the ones that don't exist yet and adding them to `tags`.
+fn tag_ids(
+    conn: &Connection,
+    tags: &mut Vec<Tag>,
+    names: &[String],
+) -> Result<Vec<i64>, Box<dyn Error>> {
+    let mut ids = Vec::with_capacity(names.len());
+    for name in names {
+        let id = match tags.iter().find(|t| t.name.eq_ignore_ascii_case(name)) {
+            Some(tag) => tag.id,
+            None => {
+                let color = TAG_COLORS[tags.len() % TAG_COLORS.len()];
+                let id = db_operations::insert_tag(conn, name, color, None)?;
+                tags.push(Tag {
+                    id,
+                    name: name.clone(),
+                    color,
+                    alert_offsets: None,
+                });
+                id
+            }
+        };
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Days;
+
+    use super::*;
+
+    fn item(date: NaiveDate, note: &str) -> Item {
+        Item {
+            uid: None,
+            date,
+            time: None,
+            note: note.into(),
+            recurrence: None,
+            unsupported_rule: None,
+            priority: Priority::Normal,
+            tags: Vec::new(),
+            alerts: None,
+            alerts_fired: Vec::new(),
+            snoozed_until: None,
+            completed_at: None,
+        }
+    }
+
+    fn notes(conn: &Connection) -> Vec<(NaiveDate, String)> {
+        let mut notes: Vec<_> = db_operations::list_reminders(conn)
+            .unwrap()
+            .into_iter()
+            .chain(db_operations::list_completed_reminders(conn).unwrap())
+            .map(|r| (r.date, r.note))
+            .collect();
+        notes.sort();
+        notes
+    }
+
+    #[test]
+    fn dry_run_counts_without_changing_the_database() {
+        let conn = db_operations::memory_db();
+        let today = Local::now().date_naive();
+        let existing =
+            db_operations::insert_reminder(&conn, today, None, "Existing", None, false, &[])
+                .unwrap();
+        db_operations::set_reminder_uid(&conn, existing, "known@example.com").unwrap();
+
+        let last_week = today - Days::new(7);
+        let next_week = today + Days::new(7);
+        let items = vec![
+            Item {
+                uid: Some("known@example.com".into()),
+                ..item(next_week, "Duplicate")
+            },
+            item(last_week, "Past"),
+            Item {
+                recurrence: Some("FREQ=DAILY".parse().unwrap()),
+                ..item(last_week, "Daily")
+            },
+            Item {
+                completed_at: last_week.and_hms_opt(12, 0, 0),
+                ..item(last_week, "Done")
+            },
+            Item {
+                uid: Some("new@example.com".into()),
+                tags: vec!["Work".into(), "work".into()],
+                unsupported_rule: Some("FREQ=MONTHLY;BYSETPOS=-1".into()),
+                ..item(next_week, "Tagged")
+            },
+        ];
+        let before = notes(&conn);
+
+        let dry_run = ImportOptions {
+            keep_past: false,
+            dry_run: true,
+        };
+        let report = import(&conn, items.clone(), &Settings::default(), dry_run).unwrap();
+        assert_eq!((report.imported, report.duplicates, report.past), (3, 1, 1));
+        assert_eq!(report.unsupported_rules, ["FREQ=MONTHLY;BYSETPOS=-1"]);
+        assert_eq!(notes(&conn), before);
+        assert!(db_operations::list_tags(&conn).unwrap().is_empty());
+
+        let options = ImportOptions::default();
+        let report = import(&conn, items, &Settings::default(), options).unwrap();
+        assert_eq!((report.imported, report.duplicates, report.past), (3, 1, 1));
+        assert_eq!(
+            notes(&conn),
+            [
+                (last_week, "Done".to_owned()),
+                (today, "Daily".to_owned()),
+                (today, "Existing".to_owned()),
+                (next_week, "Tagged".to_owned()),
+            ]
+        );
+        let tags = db_operations::list_tags(&conn).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "Work");
+
+        // Importing the same file again only finds duplicates of the entries with a UID.
+        let again = vec![Item {
+            uid: Some("new@example.com".into()),
+            ..item(next_week, "Tagged")
+        }];
+        let report = import(&conn, again, &Settings::default(), options).unwrap();
+        assert_eq!((report.imported, report.duplicates), (0, 1));
+    }
+
+    #[test]
+    fn keep_past_imports_entries_at_their_own_dates() {
+        let conn = db_operations::memory_db();
+        let last_week = Local::now().date_naive() - Days::new(7);
+        let items = vec![
+            item(last_week, "Past"),
+            Item {
+                recurrence: Some("FREQ=DAILY".parse().unwrap()),
+                ..item(last_week, "Daily")
+            },
+        ];
+        let options = ImportOptions {
+            keep_past: true,
+            dry_run: false,
+        };
+        let report = import(&conn, items, &Settings::default(), options).unwrap();
+        assert_eq!((report.imported, report.past), (2, 0));
+        assert_eq!(
+            notes(&conn),
+            [
+                (last_week, "Daily".to_owned()),
+                (last_week, "Past".to_owned())
+            ]
+        );
+    }
+}
@@ -9,7 +9,7 @@ pub fn set_repaint_context(ctx: egui::Context) {
     let _ = REPAINT_CTX.set(ctx);
 }
 
-fn request_repaint() {
+pub fn request_repaint() {
     if let Some(ctx) = REPAINT_CTX.get() {
         ctx.request_repaint();
     }
@@ -19,8 +19,18 @@ fn request_repaint() {
 pub enum TrayCommand {
     Open,
     Exit,
+    /// Snooze reminder `id` for `minutes`, requested from its notification.
+    Snooze {
+        id: i64,
+        minutes: u32,
+    },
+    /// Another process (e.g. the command line) changed the database; check reminders now.
+    Reload,
 }
 
+/// How long the notification's snooze action holds a reminder back.
+pub const NOTIFICATION_SNOOZE_MINUTES: u32 = 120;
+
 pub fn set_main_window_hwnd(hwnd: isize) {
     #[cfg(target_os = "windows")]
     {
@@ -37,31 +47,90 @@ pub fn set_main_window_hwnd(hwnd: isize) {
 #[path = "tray/win32.rs"]
 mod win32;
 
+#[cfg(target_os = "linux")]
+#[path = "tray/sni.rs"]
+mod sni;
+
+#[cfg(target_os = "linux")]
+#[path = "tray/freedesktop.rs"]
+mod freedesktop;
+
+#[cfg(all(test, target_os = "linux"))]
+#[path = "tray/test_bus.rs"]
+mod test_bus;
+
 pub fn spawn_tray(sender: Sender<TrayCommand>) {
     #[cfg(target_os = "windows")]
     {
         win32::spawn_tray(sender, request_repaint);
-        return;
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        freedesktop::spawn(sender.clone(), request_repaint);
+        sni::spawn_tray(sender, request_repaint);
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
         let _ = sender;
     }
 }
 
+/// Delivers `cmd` from outside the tray (e.g. a second launch, see [`crate::instance`]) the
+/// way a tray click would, including bringing the window back on Windows.
+pub fn send_command(sender: &Sender<TrayCommand>, cmd: TrayCommand) {
+    let _ = sender.send(cmd);
+    request_repaint();
+
+    #[cfg(target_os = "windows")]
+    if matches!(cmd, TrayCommand::Open) {
+        win32::restore_main_window();
+    }
+}
+
+/// Whether there is a tray icon to hide the window to. Without one, closing the window
+/// exits the app.
+pub fn is_available() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        true
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        sni::is_available()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+/// Re-reads the tray's labels after a language change. The Win32 menu is built each time
+/// it opens, so only the SNI item needs this.
+pub fn refresh() {
+    #[cfg(target_os = "linux")]
+    {
+        sni::refresh();
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum TrayNotificationKind {
+    /// Like `Info`, but without a sound.
+    Quiet,
     Info,
     Warning,
     Error,
 }
 
-pub fn notify(title: &str, body: &str, kind: TrayNotificationKind) {
+/// Shows a tray balloon (Windows only; SNI items have no balloons).
+pub fn show_balloon(title: &str, body: &str, kind: TrayNotificationKind) {
     #[cfg(target_os = "windows")]
     {
         win32::enqueue_notification(title, body, kind);
-        return;
     }
 
     #[cfg(not(target_os = "windows"))]
@@ -69,3 +138,36 @@ pub fn notify(title: &str, body: &str, kind: TrayNotificationKind) {
         let _ = (title, body, kind);
     }
 }
+
+/// Shows a pop-up that stays until dismissed (Windows only). `snooze` is the reminder it
+/// is about; the pop-up offers snoozing it for [`NOTIFICATION_SNOOZE_MINUTES`].
+pub fn show_message_box(title: &str, body: &str, kind: TrayNotificationKind, snooze: Option<i64>) {
+    #[cfg(target_os = "windows")]
+    {
+        win32::spawn_message_box(title, body, kind, snooze);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (title, body, kind, snooze);
+    }
+}
+
+/// Sends a desktop notification (Linux only). `snooze` is the reminder it is about; daemons
+/// that support actions offer snoozing it for [`NOTIFICATION_SNOOZE_MINUTES`].
+pub fn show_desktop_notification(
+    title: &str,
+    body: &str,
+    kind: TrayNotificationKind,
+    snooze: Option<i64>,
+) {
+    #[cfg(target_os = "linux")]
+    {
+        freedesktop::enqueue_notification(title, body, kind, snooze);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (title, body, kind, snooze);
+    }
+}
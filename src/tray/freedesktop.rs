@@ -0,0 +1,376 @@
+//! Linux notification backend: `org.freedesktop.Notifications` on the D-Bus session bus.
+//!
+//! Works with any notification daemon (GNOME Shell, Plasma, mako, dunst, ...):
+//! - [`TrayNotificationKind`] maps to the `urgency` hint (low / normal / critical); `Quiet`
+//!   also asks for no sound (`suppress-sound`).
+//! - A new notification about the same reminder replaces the one still on screen.
+//! - If the daemon supports actions, clicking the notification opens the window and a
+//!   "Snooze" button sends [`TrayCommand::Snooze`].
+//!
+//! D-Bus calls block, so they run on a worker thread fed by [`enqueue_notification`];
+//! a second thread listens for the daemon's signals.
+//!
+//! To check it without a desktop, run the app on a private bus with a stand-in daemon, e.g.
+//! `dbus-run-session -- sh -c 'dunst & cargo run'`. The tests below do the same with a mock
+//! daemon (`cargo test -- --ignored`).
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::Value;
+
+use super::{NOTIFICATION_SNOOZE_MINUTES, TrayCommand, TrayNotificationKind};
+use crate::{debug_err, i18n};
+
+static QUEUE: OnceLock<Sender<QueuedNotification>> = OnceLock::new();
+
+const ACTION_OPEN: &str = "default";
+const ACTION_SNOOZE: &str = "snooze";
+
+/// Spec default: let the daemon decide how long the notification stays up.
+const EXPIRE_DEFAULT: i32 = -1;
+
+#[derive(Debug, Clone)]
+struct QueuedNotification {
+    title: String,
+    body: String,
+    kind: TrayNotificationKind,
+    snooze: Option<i64>,
+}
+
+/// Which reminder each notification on screen is about, in both directions.
+#[derive(Default)]
+struct Shown {
+    by_reminder: HashMap<i64, u32>,
+    by_notification: HashMap<u32, i64>,
+}
+
+impl Shown {
+    fn insert(&mut self, reminder: i64, notification: u32) {
+        if let Some(old) = self.by_reminder.insert(reminder, notification) {
+            self.by_notification.remove(&old);
+        }
+        self.by_notification.insert(notification, reminder);
+    }
+
+    fn remove(&mut self, notification: u32) {
+        if let Some(reminder) = self.by_notification.remove(&notification) {
+            self.by_reminder.remove(&reminder);
+        }
+    }
+}
+
+pub(super) fn spawn(sender: Sender<TrayCommand>, request_repaint: fn()) {
+    let (tx, rx) = mpsc::channel();
+    if QUEUE.set(tx).is_err() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        if let Err(err) = run(rx, sender, request_repaint) {
+            debug_err!("desktop notifications unavailable: {err}");
+        }
+    });
+}
+
+pub(super) fn enqueue_notification(
+    title: &str,
+    body: &str,
+    kind: TrayNotificationKind,
+    snooze: Option<i64>,
+) {
+    let Some(queue) = QUEUE.get() else {
+        return;
+    };
+
+    let _ = queue.send(QueuedNotification {
+        title: title.to_owned(),
+        body: body.to_owned(),
+        kind,
+        snooze,
+    });
+}
+
+fn run(
+    queue: Receiver<QueuedNotification>,
+    sender: Sender<TrayCommand>,
+    request_repaint: fn(),
+) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+    let proxy = Proxy::new(
+        &connection,
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+    )?;
+
+    let capabilities: Vec<String> = proxy.call("GetCapabilities", &()).unwrap_or_default();
+    let actions = capabilities.iter().any(|c| c == "actions");
+
+    let shown = Arc::new(Mutex::new(Shown::default()));
+    listen(&proxy, Arc::clone(&shown), sender, request_repaint)?;
+
+    for n in queue {
+        let replaces = n
+            .snooze
+            .and_then(|id| shown.lock().ok()?.by_reminder.get(&id).copied())
+            .unwrap_or(0);
+
+        match show(&proxy, &n, replaces, actions) {
+            Ok(notification) => {
+                if let (Some(id), Ok(mut shown)) = (n.snooze, shown.lock()) {
+                    shown.insert(id, notification);
+                }
+            }
+            Err(err) => debug_err!("failed to show notification: {err}"),
+        }
+    }
+    Ok(())
+}
+
+/// Sends one `Notify` call and returns the daemon's id for the notification.
+fn show(
+    proxy: &Proxy<'_>,
+    n: &QueuedNotification,
+    replaces: u32,
+    actions: bool,
+) -> zbus::Result<u32> {
+    let lang = i18n::language();
+    let mut action_list = Vec::new();
+    if actions {
+        action_list.extend([ACTION_OPEN, i18n::tray_open(lang)]);
+        if n.snooze.is_some() {
+            action_list.extend([ACTION_SNOOZE, i18n::notif_snooze_action(lang)]);
+        }
+    }
+
+    let urgency: u8 = match n.kind {
+        TrayNotificationKind::Quiet | TrayNotificationKind::Info => 0,
+        TrayNotificationKind::Warning => 1,
+        TrayNotificationKind::Error => 2,
+    };
+    let quiet = matches!(n.kind, TrayNotificationKind::Quiet);
+    let hints = HashMap::from([
+        ("urgency", Value::U8(urgency)),
+        ("suppress-sound", Value::Bool(quiet)),
+    ]);
+
+    proxy.call(
+        "Notify",
+        &(
+            i18n::app_title(lang),
+            replaces,
+            "appointment-soon",
+            n.title.as_str(),
+            n.body.as_str(),
+            action_list,
+            hints,
+            EXPIRE_DEFAULT,
+        ),
+    )
+}
+
+/// Forwards clicks and action buttons as [`TrayCommand`]s and forgets closed notifications.
+fn listen(
+    proxy: &Proxy<'static>,
+    shown: Arc<Mutex<Shown>>,
+    sender: Sender<TrayCommand>,
+    request_repaint: fn(),
+) -> zbus::Result<()> {
+    // One stream for both signals, so `ActionInvoked` is always handled before the
+    // `NotificationClosed` that usually follows it.
+    let signals = proxy.receive_all_signals()?;
+
+    std::thread::spawn(move || {
+        for msg in signals {
+            let header = msg.header();
+            let Some(member) = header.member() else {
+                continue;
+            };
+            let Ok(mut shown) = shown.lock() else {
+                return;
+            };
+
+            match member.as_str() {
+                "ActionInvoked" => {
+                    let Ok((notification, action)) = msg.body().deserialize::<(u32, String)>()
+                    else {
+                        continue;
+                    };
+                    // Signals for other clients' notifications arrive here too.
+                    let Some(&reminder) = shown.by_notification.get(&notification) else {
+                        continue;
+                    };
+
+                    let cmd = match action.as_str() {
+                        ACTION_OPEN => TrayCommand::Open,
+                        ACTION_SNOOZE => TrayCommand::Snooze {
+                            id: reminder,
+                            minutes: NOTIFICATION_SNOOZE_MINUTES,
+                        },
+                        _ => continue,
+                    };
+                    let _ = sender.send(cmd);
+                    request_repaint();
+                }
+                "NotificationClosed" => {
+                    if let Ok((notification, _reason)) = msg.body().deserialize::<(u32, u32)>() {
+                        shown.remove(notification);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{Duration, Instant};
+
+    use zbus::blocking::connection;
+    use zbus::names::BusName;
+    use zbus::zvariant::OwnedValue;
+
+    use super::super::test_bus;
+    use super::*;
+
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+
+    /// What the app sent in one `Notify` call, and the id it got back.
+    #[derive(Debug, Clone)]
+    struct Notified {
+        id: u32,
+        replaces_id: u32,
+        actions: Vec<String>,
+        urgency: u8,
+        suppress_sound: bool,
+    }
+
+    /// A notification daemon that supports actions and records every `Notify`.
+    #[derive(Clone, Default)]
+    struct Daemon {
+        notified: Arc<Mutex<Vec<Notified>>>,
+        last_id: Arc<AtomicU32>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Daemon {
+        fn get_capabilities(&self) -> Vec<String> {
+            vec!["actions".into(), "body".into()]
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: String,
+            replaces_id: u32,
+            _app_icon: String,
+            _summary: String,
+            _body: String,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let id = match replaces_id {
+                0 => self.last_id.fetch_add(1, Ordering::Relaxed) + 1,
+                id => id,
+            };
+            let hint = |name: &str| hints.get(name).and_then(|v| v.try_clone().ok());
+            self.notified.lock().unwrap().push(Notified {
+                id,
+                replaces_id,
+                actions,
+                urgency: hint("urgency").and_then(|v| u8::try_from(v).ok()).unwrap(),
+                suppress_sound: hint("suppress-sound")
+                    .and_then(|v| bool::try_from(v).ok())
+                    .unwrap_or(false),
+            });
+            id
+        }
+    }
+
+    impl Daemon {
+        /// Waits for the `n`th `Notify` call (1-based).
+        fn wait_for(&self, n: usize) -> Notified {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                if let Some(notified) = self.notified.lock().unwrap().get(n - 1) {
+                    return notified.clone();
+                }
+                assert!(Instant::now() < deadline, "no notification #{n}");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon; run with `cargo test -- --ignored`"]
+    fn maps_urgency_replaces_and_snoozes() {
+        let address = test_bus::address();
+        let daemon = Daemon::default();
+        let daemon_conn = connection::Builder::address(address)
+            .unwrap()
+            .name(INTERFACE)
+            .unwrap()
+            .serve_at(PATH, daemon.clone())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let (queue, rx) = mpsc::channel();
+        let (sender, commands) = mpsc::channel();
+        std::thread::spawn(move || run(rx, sender, || {}));
+        let send = |kind, snooze| {
+            queue
+                .send(QueuedNotification {
+                    title: "title".into(),
+                    body: "body".into(),
+                    kind,
+                    snooze,
+                })
+                .unwrap();
+        };
+
+        send(TrayNotificationKind::Error, Some(7));
+        let first = daemon.wait_for(1);
+        assert_eq!((first.replaces_id, first.urgency), (0, 2));
+        assert!(!first.suppress_sound);
+        assert!(first.actions.iter().any(|a| a == ACTION_SNOOZE));
+
+        // A second notice about the same reminder takes the first one's place.
+        send(TrayNotificationKind::Warning, Some(7));
+        let second = daemon.wait_for(2);
+        assert_eq!((second.replaces_id, second.urgency), (first.id, 1));
+
+        send(TrayNotificationKind::Quiet, None);
+        let third = daemon.wait_for(3);
+        assert_eq!((third.replaces_id, third.urgency), (0, 0));
+        assert!(third.suppress_sound);
+        assert!(!third.actions.iter().any(|a| a == ACTION_SNOOZE));
+
+        for notification in [third.id, first.id] {
+            daemon_conn
+                .emit_signal(
+                    None::<BusName<'_>>,
+                    PATH,
+                    INTERFACE,
+                    "ActionInvoked",
+                    &(notification, ACTION_SNOOZE),
+                )
+                .unwrap();
+        }
+        // The first signal is about no reminder and is ignored.
+        match commands.recv_timeout(Duration::from_secs(5)) {
+            Ok(TrayCommand::Snooze { id: 7, minutes }) => {
+                assert_eq!(minutes, NOTIFICATION_SNOOZE_MINUTES);
+            }
+            other => panic!("expected a snooze for reminder 7, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Linux tray backend: a StatusNotifierItem (SNI) served over the D-Bus session bus.
+//!
+//! KDE Plasma, most panels (waybar, xfce4-panel, ...) and GNOME with the AppIndicator
+//! extension host SNI items. Without a `org.kde.StatusNotifierWatcher` on the bus there is no
+//! tray; [`is_available`] reports that so the window can close normally instead of hiding.
+//!
+//! To try it without a desktop, run the app on a private bus with a watcher, e.g.
+//! `dbus-run-session -- sh -c 'snixembed & cargo run'`. The tests below do the same with a
+//! stand-in watcher (`cargo test -- --ignored`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::Duration;
+
+use ksni::blocking::{Handle, TrayMethods as _};
+use ksni::menu::StandardItem;
+use ksni::{MenuItem, OfflineReason, ToolTip};
+
+use super::TrayCommand;
+use crate::{debug_err, debug_log, i18n};
+
+static HANDLE: OnceLock<Handle<SilliTray>> = OnceLock::new();
+static AVAILABLE: AtomicBool = AtomicBool::new(false);
+/// Set once the first connection attempt has succeeded or failed; until then
+/// [`is_available`] waits rather than guess.
+static SETTLED: Mutex<bool> = Mutex::new(false);
+static SETTLED_CV: Condvar = Condvar::new();
+/// How long closing the window may wait for the tray to come up.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(3);
+
+struct SilliTray {
+    sender: Sender<TrayCommand>,
+    request_repaint: fn(),
+    icon: Vec<ksni::Icon>,
+}
+
+impl SilliTray {
+    fn send(&self, cmd: TrayCommand) {
+        let _ = self.sender.send(cmd);
+        (self.request_repaint)();
+    }
+}
+
+impl ksni::Tray for SilliTray {
+    fn id(&self) -> String {
+        "SilliReminder".into()
+    }
+
+    fn title(&self) -> String {
+        i18n::app_title(i18n::language()).into()
+    }
+
+    fn icon_name(&self) -> String {
+        // Only used by hosts that ignore the pixmap.
+        "appointment-soon".into()
+    }
+
+    fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+        self.icon.clone()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: i18n::tray_tooltip(i18n::language()).into(),
+            ..Default::default()
+        }
+    }
+
+    /// Left click opens the window, like double-clicking the Windows tray icon.
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.send(TrayCommand::Open);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let lang = i18n::language();
+        vec![
+            StandardItem {
+                label: i18n::tray_open(lang).into(),
+                activate: Box::new(|tray: &mut Self| tray.send(TrayCommand::Open)),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: i18n::tray_exit(lang).into(),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|tray: &mut Self| tray.send(TrayCommand::Exit)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+
+    fn watcher_online(&self) {
+        AVAILABLE.store(true, Ordering::Relaxed);
+    }
+
+    fn watcher_offline(&self, reason: OfflineReason) -> bool {
+        debug_log!("tray host went away: {reason:?}");
+        AVAILABLE.store(false, Ordering::Relaxed);
+        // Keep serving; the item re-registers when a host comes back.
+        true
+    }
+}
+
+/// Registers the tray item on the session bus.
+///
+/// Connecting can take a moment, so it runs on its own thread; the D-Bus service then keeps
+/// running on a thread owned by `ksni`.
+pub(super) fn spawn_tray(sender: Sender<TrayCommand>, request_repaint: fn()) {
+    std::thread::spawn(move || {
+        let tray = SilliTray {
+            sender,
+            request_repaint,
+            icon: icon_pixmap(),
+        };
+
+        match tray.spawn() {
+            Ok(handle) => {
+                AVAILABLE.store(true, Ordering::Relaxed);
+                let _ = HANDLE.set(handle);
+            }
+            Err(err) => debug_err!("no system tray available: {err}"),
+        }
+        *SETTLED.lock().unwrap_or_else(|p| p.into_inner()) = true;
+        SETTLED_CV.notify_all();
+    });
+}
+
+/// Whether the item is registered with a host. While the first connection is still in
+/// progress this waits for its outcome, so closing the window right after start-up neither
+/// quits the app nor hides it to a tray that never appears.
+pub(super) fn is_available() -> bool {
+    let settled = SETTLED.lock().unwrap_or_else(|p| p.into_inner());
+    let _ = SETTLED_CV.wait_timeout_while(settled, SETTLE_TIMEOUT, |settled| !*settled);
+    AVAILABLE.load(Ordering::Relaxed)
+}
+
+/// Asks the host to re-read the title and menu, e.g. after a language change.
+pub(super) fn refresh() {
+    if let Some(handle) = HANDLE.get() {
+        handle.update(|_| {});
+    }
+}
+
+/// The app icon as SNI wants it: ARGB32 in network byte order.
+fn icon_pixmap() -> Vec<ksni::Icon> {
+    let Ok(icon) = eframe::icon_data::from_png_bytes(crate::ICON_PNG) else {
+        return Vec::new();
+    };
+
+    let data = icon
+        .rgba
+        .chunks_exact(4)
+        .flat_map(|px| [px[3], px[0], px[1], px[2]])
+        .collect();
+    vec![ksni::Icon {
+        width: icon.width as i32,
+        height: icon.height as i32,
+        data,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::mpsc;
+    use std::sync::{Arc, Mutex};
+
+    use zbus::blocking::{Connection, Proxy, connection};
+    use zbus::zvariant::{OwnedValue, Value};
+
+    use super::super::test_bus;
+    use super::*;
+
+    /// Stands in for the panel: accepts every item and says a host is there.
+    #[derive(Clone, Default)]
+    struct Watcher {
+        items: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[zbus::interface(name = "org.kde.StatusNotifierWatcher")]
+    impl Watcher {
+        fn register_status_notifier_item(&self, service: String) {
+            self.items.lock().unwrap().push(service);
+        }
+
+        fn register_status_notifier_host(&self, _service: String) {}
+
+        #[zbus(property)]
+        fn registered_status_notifier_items(&self) -> Vec<String> {
+            self.items.lock().unwrap().clone()
+        }
+
+        #[zbus(property)]
+        fn is_status_notifier_host_registered(&self) -> bool {
+            true
+        }
+
+        #[zbus(property)]
+        fn protocol_version(&self) -> i32 {
+            0
+        }
+    }
+
+    /// A `com.canonical.dbusmenu` layout node: id, properties, children.
+    type Layout = (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>);
+
+    #[test]
+    #[ignore = "needs dbus-daemon; run with `cargo test -- --ignored`"]
+    fn registers_and_serves_open_and_exit() {
+        let address = test_bus::address();
+        let watcher = Watcher::default();
+        let _watcher_conn = connection::Builder::address(address)
+            .unwrap()
+            .name("org.kde.StatusNotifierWatcher")
+            .unwrap()
+            .serve_at("/StatusNotifierWatcher", watcher.clone())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        spawn_tray(tx, || {});
+        assert!(is_available());
+        let service = watcher.items.lock().unwrap().first().cloned();
+        let service = service.expect("the item registered with the watcher");
+
+        let client = Connection::session().unwrap();
+        let menu = Proxy::new(&client, service, "/MenuBar", "com.canonical.dbusmenu").unwrap();
+        let (_revision, (_, _, children)): (u32, Layout) = menu
+            .call("GetLayout", &(0i32, -1i32, Vec::<String>::new()))
+            .unwrap();
+        let labels: HashMap<String, i32> = children
+            .into_iter()
+            .filter_map(|child| {
+                let (id, props, _) = Layout::try_from(child).ok()?;
+                let label = String::try_from(props.get("label")?.try_clone().ok()?).ok()?;
+                Some((label, id))
+            })
+            .collect();
+
+        let lang = i18n::language();
+        for (label, expected) in [
+            (i18n::tray_open(lang), TrayCommand::Open),
+            (i18n::tray_exit(lang), TrayCommand::Exit),
+        ] {
+            let id = *labels
+                .get(label)
+                .unwrap_or_else(|| panic!("no {label:?} in {labels:?}"));
+            let () = menu
+                .call("Event", &(id, "clicked", Value::I32(0), 0u32))
+                .unwrap();
+            let cmd = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(
+                std::mem::discriminant(&cmd),
+                std::mem::discriminant(&expected),
+                "{label:?} sent {cmd:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! A private D-Bus session bus for the Linux tray tests. They are `#[ignore]`d because they
+//! need `dbus-daemon`; run them with `cargo test -- --ignored`.
+
+use std::io::{BufRead as _, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Address of a bus started for this test run. It is also exported as
+/// `DBUS_SESSION_BUS_ADDRESS`, since `ksni` only connects to the session bus.
+pub(super) fn address() -> &'static str {
+    static ADDRESS: OnceLock<String> = OnceLock::new();
+    ADDRESS.get_or_init(|| {
+        // The shell stops the daemon once its stdin (our pipe) closes, i.e. when the test
+        // process exits.
+        let mut shell = Command::new("sh")
+            .arg("-c")
+            .arg("dbus-daemon --session --nofork --print-address=1 & read _; kill $!")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start dbus-daemon");
+        let mut line = String::new();
+        BufReader::new(shell.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        // `wait` would close stdin, so the thread keeps its own handle: it holds the pipe
+        // open for the rest of the run.
+        let stdin = shell.stdin.take();
+        std::thread::spawn(move || {
+            let _stdin = stdin;
+            shell.wait()
+        });
+
+        let address = line.trim().to_owned();
+        assert!(!address.is_empty(), "dbus-daemon printed no address");
+        // SAFETY: set once, before any test connects to the session bus.
+        unsafe { std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &address) };
+        address
+    })
+}
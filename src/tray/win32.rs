@@ -28,7 +28,7 @@ use windows::Win32::UI::Shell::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::core::{PCWSTR, w};
 
-use super::TrayCommand;
+use super::{NOTIFICATION_SNOOZE_MINUTES, TrayCommand};
 use crate::i18n;
 use crate::tray::TrayNotificationKind;
 
@@ -66,6 +66,7 @@ pub(super) fn spawn_tray(sender: Sender<TrayCommand>, request_repaint: fn()) {
     std::thread::spawn(move || run_tray_loop());
 }
 
+/// Queues a balloon; the tray thread shows it (the icon belongs to that thread).
 pub(super) fn enqueue_notification(title: &str, body: &str, kind: TrayNotificationKind) {
     let Some(queue) = NOTIFY_QUEUE.get() else {
         return;
@@ -125,6 +126,7 @@ fn show_balloon(hwnd: HWND, n: &QueuedNotification) {
     copy_wide_trunc(&mut nid.szInfo, &n.body);
 
     nid.dwInfoFlags = match n.kind {
+        TrayNotificationKind::Quiet => NIIF_INFO | NIIF_NOSOUND,
         TrayNotificationKind::Info => NIIF_INFO,
         TrayNotificationKind::Warning => NIIF_WARNING,
         TrayNotificationKind::Error => NIIF_ERROR,
@@ -136,31 +138,64 @@ fn show_balloon(hwnd: HWND, n: &QueuedNotification) {
     }
 }
 
-fn spawn_message_box(n: QueuedNotification) {
-    // Spawn a dedicated thread so we don't block the tray message pump.
+/// Shows a modal message box that stays until dismissed. With `snooze`, it asks whether to
+/// snooze that reminder.
+pub(super) fn spawn_message_box(
+    title: &str,
+    body: &str,
+    kind: TrayNotificationKind,
+    snooze: Option<i64>,
+) {
+    let title = title.to_owned();
+    let body = body.to_owned();
+
+    // A dedicated thread, since the box blocks until the user answers it.
     std::thread::spawn(move || {
-        let mut title_w: Vec<u16> = n.title.encode_utf16().collect();
+        let mut title_w: Vec<u16> = title.encode_utf16().collect();
         title_w.push(0);
 
-        let mut body_w: Vec<u16> = n.body.encode_utf16().collect();
+        // Balloons can't carry buttons, so the snooze action lives on the message box:
+        // "Yes" snoozes, "No" just dismisses.
+        let body = match snooze {
+            Some(_) => format!(
+                "{}\n\n{}",
+                body,
+                i18n::notif_snooze_prompt(i18n::language())
+            ),
+            None => body,
+        };
+        let mut body_w: Vec<u16> = body.encode_utf16().collect();
         body_w.push(0);
 
-        let icon = match n.kind {
-            TrayNotificationKind::Info => MB_ICONINFORMATION,
+        let icon = match kind {
+            TrayNotificationKind::Quiet | TrayNotificationKind::Info => MB_ICONINFORMATION,
             TrayNotificationKind::Warning => MB_ICONWARNING,
             TrayNotificationKind::Error => MB_ICONERROR,
         };
 
-        let flags = MB_OK | icon | MB_TOPMOST | MB_SETFOREGROUND;
+        let buttons = if snooze.is_some() { MB_YESNO } else { MB_OK };
+        let flags = buttons | icon | MB_TOPMOST | MB_SETFOREGROUND;
 
-        unsafe {
+        let result = unsafe {
             // SAFETY: Win32 modal dialog. Strings are null-terminated and live for the call.
-            let _ = MessageBoxW(
+            MessageBoxW(
                 None,
                 PCWSTR(body_w.as_ptr()),
                 PCWSTR(title_w.as_ptr()),
                 flags,
-            );
+            )
+        };
+
+        if let Some(id) = snooze
+            && result == IDYES
+            && let Some(sender) = TRAY_SENDER.get()
+        {
+            let _ = sender.send(TrayCommand::Snooze {
+                id,
+                minutes: NOTIFICATION_SNOOZE_MINUTES,
+            });
+            request_repaint();
+            wake_main_window();
         }
     });
 }
@@ -192,6 +227,12 @@ fn wake_main_window() {
     }
 }
 
+/// Wakes and restores the main window, as clicking the tray icon does.
+pub(super) fn restore_main_window() {
+    wake_main_window();
+    restore_main_window_delayed(RESTORE_DELAY_MS);
+}
+
 /// Restores the main window after a small delay.
 ///
 /// We delay the restore to reduce the "black panel" flash: it gives egui a moment
@@ -339,7 +380,6 @@ unsafe extern "system" fn wnd_proc(
                 };
                 if let Some(n) = n {
                     show_balloon(hwnd, &n);
-                    spawn_message_box(n);
                 }
             }
             LRESULT(0)
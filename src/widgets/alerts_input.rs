@@ -0,0 +1,74 @@
+use eframe::egui::{self, TextEdit, Ui, Widget};
+
+use crate::alerts;
+use crate::i18n::{self, Language};
+
+/// Text input for a list of alert lead times, e.g. `30d, 14d, 1d, 2h` (see [`alerts`]).
+///
+/// The selection only changes when the text parses; invalid text is shown in red and
+/// reverts once the field loses focus.
+pub struct AlertsInput<'a> {
+    selection: &'a mut Vec<i64>,
+    id_salt: Option<&'a str>,
+    language: Language,
+}
+
+impl<'a> AlertsInput<'a> {
+    pub fn new(selection: &'a mut Vec<i64>) -> Self {
+        Self {
+            selection,
+            id_salt: None,
+            language: i18n::language(),
+        }
+    }
+
+    /// Set tooltip language (defaults to detected app language).
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Must be set if you have multiple alert inputs in the same `Ui`.
+    pub fn id_salt(mut self, id_salt: &'a str) -> Self {
+        self.id_salt = Some(id_salt);
+        self
+    }
+}
+
+impl Widget for AlertsInput<'_> {
+    fn ui(self, ui: &mut Ui) -> egui::Response {
+        let id = ui.make_persistent_id(self.id_salt);
+
+        let mut text = ui
+            .data_mut(|data| data.get_temp::<String>(id))
+            .unwrap_or_else(|| alerts::format_offsets(self.selection));
+        let parsed = alerts::parse_offsets(&text);
+
+        let mut edit = TextEdit::singleline(&mut text)
+            .id_salt(id)
+            .desired_width(160.0)
+            .hint_text(alerts::format_offsets(&alerts::DEFAULT_OFFSETS));
+        if parsed.is_err() {
+            edit = edit.text_color(ui.visuals().error_fg_color);
+        }
+
+        let inner = ui.scope(|ui| ui.add(edit).on_hover_text(i18n::alerts_hint(self.language)));
+
+        let mut response = inner.response;
+        if inner.inner.changed()
+            && let Ok(offsets) = alerts::parse_offsets(&text)
+            && offsets != *self.selection
+        {
+            *self.selection = offsets;
+            response.mark_changed();
+        }
+
+        // Outside of editing, show the normalized form of the current selection.
+        if !inner.inner.has_focus() {
+            text = alerts::format_offsets(self.selection);
+        }
+        ui.data_mut(|data| data.insert_temp(id, text));
+
+        response
+    }
+}
@@ -1,3 +1,9 @@
+pub mod alerts_input;
 pub mod date_picker_pl;
+pub mod recurrence_input;
+pub mod time_input;
 
+pub use alerts_input::AlertsInput;
 pub use date_picker_pl::DatePickerPlButton;
+pub use recurrence_input::RecurrenceInput;
+pub use time_input::TimeInput;
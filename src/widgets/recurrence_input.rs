@@ -0,0 +1,183 @@
+use chrono::{Datelike as _, NaiveDate, Weekday};
+use eframe::egui::{self, ComboBox, DragValue, Ui, Widget};
+
+use crate::i18n::{self, Language};
+use crate::recurrence::{Frequency, Recurrence};
+
+use super::DatePickerPlButton;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    Never,
+    Until,
+    Count,
+}
+
+/// Editor for an optional [`Recurrence`]: frequency, interval, weekdays / last day of month
+/// and how the series ends.
+///
+/// `start` is the date of the first occurrence; it seeds the weekday selection and the
+/// default end date.
+pub struct RecurrenceInput<'a> {
+    selection: &'a mut Option<Recurrence>,
+    start: NaiveDate,
+    id_salt: &'a str,
+    language: Language,
+}
+
+impl<'a> RecurrenceInput<'a> {
+    pub fn new(selection: &'a mut Option<Recurrence>, start: NaiveDate) -> Self {
+        Self {
+            selection,
+            start,
+            id_salt: "recurrence_input",
+            language: i18n::language(),
+        }
+    }
+
+    /// Set labels language (defaults to detected app language).
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Must be set if you have multiple recurrence inputs in the same `Ui`.
+    pub fn id_salt(mut self, id_salt: &'a str) -> Self {
+        self.id_salt = id_salt;
+        self
+    }
+}
+
+impl Widget for RecurrenceInput<'_> {
+    fn ui(self, ui: &mut Ui) -> egui::Response {
+        let lang = self.language;
+        let before = self.selection.clone();
+
+        let mut response = ui
+            .horizontal_wrapped(|ui| {
+                let mut frequency = self.selection.as_ref().map(|r| r.frequency);
+                ComboBox::from_id_salt((self.id_salt, "frequency"))
+                    .selected_text(i18n::recurrence_frequency(lang, frequency))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut frequency,
+                            None,
+                            i18n::recurrence_frequency(lang, None),
+                        );
+                        for f in Frequency::ALL {
+                            ui.selectable_value(
+                                &mut frequency,
+                                Some(f),
+                                i18n::recurrence_frequency(lang, Some(f)),
+                            );
+                        }
+                    });
+
+                match frequency {
+                    None => *self.selection = None,
+                    Some(f) => match self.selection {
+                        Some(rule) if rule.frequency == f => {}
+                        _ => *self.selection = Some(Recurrence::new(f)),
+                    },
+                }
+
+                let Some(rule) = self.selection.as_mut() else {
+                    return;
+                };
+
+                ui.label(i18n::recurrence_every(lang));
+                ui.add(DragValue::new(&mut rule.interval).range(1..=999));
+
+                match rule.frequency {
+                    Frequency::Weekly => {
+                        if rule.by_weekday.is_empty() {
+                            rule.by_weekday.push(self.start.weekday());
+                        }
+                        let names = i18n::date_picker_weekdays(lang);
+                        for (wd, name) in WEEKDAYS.into_iter().zip(names) {
+                            let mut on = rule.by_weekday.contains(&wd);
+                            if ui.toggle_value(&mut on, name).changed() {
+                                if on {
+                                    rule.by_weekday.push(wd);
+                                    rule.by_weekday.sort_by_key(|d| d.num_days_from_monday());
+                                } else if rule.by_weekday.len() > 1 {
+                                    rule.by_weekday.retain(|d| *d != wd);
+                                }
+                            }
+                        }
+                    }
+                    Frequency::Monthly => {
+                        let mut last_day = rule.by_month_day == [-1];
+                        if ui
+                            .checkbox(&mut last_day, i18n::recurrence_last_day(lang))
+                            .changed()
+                        {
+                            rule.by_month_day = if last_day { vec![-1] } else { Vec::new() };
+                        }
+                    }
+                    Frequency::Daily | Frequency::Yearly => {}
+                }
+
+                let mut end = match (rule.until, rule.count) {
+                    (Some(_), _) => End::Until,
+                    (None, Some(_)) => End::Count,
+                    (None, None) => End::Never,
+                };
+                ui.label(i18n::recurrence_ends(lang));
+                ComboBox::from_id_salt((self.id_salt, "end"))
+                    .selected_text(end_label(lang, end))
+                    .show_ui(ui, |ui| {
+                        for e in [End::Never, End::Until, End::Count] {
+                            ui.selectable_value(&mut end, e, end_label(lang, e));
+                        }
+                    });
+
+                match end {
+                    End::Never => {
+                        rule.until = None;
+                        rule.count = None;
+                    }
+                    End::Until => {
+                        rule.count = None;
+                        let until = rule.until.get_or_insert(self.start);
+                        ui.add(
+                            DatePickerPlButton::new(until)
+                                .id_salt(self.id_salt)
+                                .format("%Y-%m-%d")
+                                .language(lang),
+                        );
+                    }
+                    End::Count => {
+                        rule.until = None;
+                        let count = rule.count.get_or_insert(10);
+                        ui.add(DragValue::new(count).range(1..=999).suffix("×"));
+                    }
+                }
+            })
+            .response;
+
+        if *self.selection != before {
+            response.mark_changed();
+        }
+
+        response
+    }
+}
+
+fn end_label(lang: Language, end: End) -> &'static str {
+    match end {
+        End::Never => i18n::recurrence_end_never(lang),
+        End::Until => i18n::recurrence_end_until(lang),
+        End::Count => i18n::recurrence_end_count(lang),
+    }
+}
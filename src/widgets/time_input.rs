@@ -0,0 +1,97 @@
+use chrono::{NaiveTime, Timelike as _};
+use eframe::egui::{self, DragValue, Ui, Widget};
+
+use crate::i18n::{self, Language};
+
+/// Optional time-of-day input: a toggle plus hour/minute fields.
+///
+/// When the toggle is off the selection is `None` ("any time that day").
+pub struct TimeInput<'a> {
+    selection: &'a mut Option<NaiveTime>,
+    id_salt: Option<&'a str>,
+    language: Language,
+}
+
+impl<'a> TimeInput<'a> {
+    pub fn new(selection: &'a mut Option<NaiveTime>) -> Self {
+        Self {
+            selection,
+            id_salt: None,
+            language: i18n::language(),
+        }
+    }
+
+    /// Set tooltip language (defaults to detected app language).
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Must be set if you have multiple time inputs in the same `Ui`.
+    pub fn id_salt(mut self, id_salt: &'a str) -> Self {
+        self.id_salt = Some(id_salt);
+        self
+    }
+}
+
+impl Widget for TimeInput<'_> {
+    fn ui(self, ui: &mut Ui) -> egui::Response {
+        let id = ui.make_persistent_id(self.id_salt);
+
+        // Remember the last picked time so toggling off/on doesn't reset it.
+        let mut last = ui
+            .data_mut(|data| data.get_persisted::<(u32, u32)>(id))
+            .unwrap_or((9, 0));
+        if let Some(t) = self.selection {
+            last = (t.hour(), t.minute());
+        }
+
+        let mut enabled = self.selection.is_some();
+        let (mut hour, mut minute) = last;
+
+        let inner = ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+
+            let mut changed = ui
+                .checkbox(&mut enabled, "🕑")
+                .on_hover_text(i18n::ui_time_toggle(self.language))
+                .changed();
+
+            ui.add_enabled_ui(enabled, |ui| {
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut hour)
+                            .range(0..=23)
+                            .custom_formatter(|v, _| format!("{v:02}")),
+                    )
+                    .changed();
+                ui.label(":");
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut minute)
+                            .range(0..=59)
+                            .custom_formatter(|v, _| format!("{v:02}")),
+                    )
+                    .changed();
+            });
+
+            changed
+        });
+
+        ui.data_mut(|data| data.insert_persisted(id, (hour, minute)));
+
+        let new_selection = if enabled {
+            NaiveTime::from_hms_opt(hour, minute, 0)
+        } else {
+            None
+        };
+
+        let mut response = inner.response;
+        if inner.inner && new_selection != *self.selection {
+            *self.selection = new_selection;
+            response.mark_changed();
+        }
+
+        response
+    }
+}